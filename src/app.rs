@@ -0,0 +1,170 @@
+use std::any::TypeId;
+use std::collections::HashSet;
+use crate::resources::Resource;
+use crate::state::{State, States};
+use crate::systems::{ParallelSystem, QuerySystem, ScheduleError, Scheduler, Stage, System, SystemConfig, TransformSystem};
+use crate::world::World;
+
+/// A reusable piece of app setup: resources, systems, a runner
+pub trait Plugin {
+    fn build(&self, app: &mut App);
+}
+
+/// Drives the app once it is set up, e.g. a window's event loop
+type Runner = Box<dyn FnOnce(&mut App)>;
+
+/// A world plus the scheduler that updates it, assembled from plugins.
+///
+/// ```no_run
+/// use rocket_engine::*;
+///
+/// let mut app = App::new();
+/// app.add_plugin(RenderPlugin::default())
+///     .add_plugin(InputPlugin::default())
+///     .add_plugin(PhysicsPlugin::default())
+///     .add_plugin(ScenePlugin::new("scenes/example_scene.ron"));
+/// app.run().unwrap();
+/// ```
+pub struct App {
+    pub world: World,
+    pub scheduler: Scheduler,
+    /// Plugins built so far, so shared dependencies are only built once
+    plugins: HashSet<TypeId>,
+    runner: Option<Runner>,
+}
+
+impl App {
+    /// An empty world whose scheduler already propagates transforms
+    pub fn new() -> Self {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(TransformSystem::new())).in_stage(Stage::PostUpdate);
+        Self {
+            world: World::new(),
+            scheduler,
+            plugins: HashSet::new(),
+            runner: None,
+        }
+    }
+
+    /// Build a plugin into the app; adding the same plugin type again does nothing
+    pub fn add_plugin<P: Plugin + 'static>(&mut self, plugin: P) -> &mut Self {
+        if self.plugins.insert(TypeId::of::<P>()) {
+            plugin.build(self);
+        }
+        self
+    }
+
+    /// Whether a plugin of type `P` has been added
+    pub fn has_plugin<P: Plugin + 'static>(&self) -> bool {
+        self.plugins.contains(&TypeId::of::<P>())
+    }
+
+    /// Insert a resource into the world, replacing any previous value
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    /// Start state machine `S` in `initial` and apply its transitions every frame
+    pub fn add_state<S: State>(&mut self, initial: S) -> &mut Self {
+        self.world.insert_resource(States::new(initial));
+        self.scheduler.add_state::<S>();
+        self
+    }
+
+    /// Add a system, as `Scheduler::add_system`
+    pub fn add_system(&mut self, system: Box<dyn System>) -> SystemConfig<'_> {
+        self.scheduler.add_system(system)
+    }
+
+    /// Add a query-based system, as `Scheduler::add_query_system`
+    pub fn add_query_system<T: QuerySystem + 'static>(&mut self, system: T) -> SystemConfig<'_> {
+        self.scheduler.add_query_system(system)
+    }
+
+    /// Add a parallel system, as `Scheduler::add_parallel_system`
+    pub fn add_parallel_system<T: ParallelSystem + 'static>(&mut self, system: T) -> SystemConfig<'_> {
+        self.scheduler.add_parallel_system(system)
+    }
+
+    /// Replace the function `run` hands the app to
+    pub fn set_runner(&mut self, runner: impl FnOnce(&mut App) + 'static) -> &mut Self {
+        self.runner = Some(Box::new(runner));
+        self
+    }
+
+    /// Run every system once
+    pub fn update(&mut self, dt: f32) {
+        self.scheduler.update(&mut self.world, dt);
+    }
+
+    /// Resolve the schedule, then hand the app to its runner. Without a
+    /// runner (no windowed `RenderPlugin`), runs a single update.
+    pub fn run(&mut self) -> Result<(), ScheduleError> {
+        self.scheduler.build()?;
+        match self.runner.take() {
+            Some(runner) => runner(self),
+            None => self.update(0.0),
+        }
+        Ok(())
+    }
+
+    /// Take the world and scheduler, for hosts with their own loop like the editor
+    pub fn into_parts(self) -> (World, Scheduler) {
+        (self.world, self.scheduler)
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plugins::{PhysicsPlugin, ScenePlugin};
+    use super::*;
+
+    #[derive(Default)]
+    struct Spawned(u32);
+
+    impl Resource for Spawned {}
+
+    struct CountingPlugin;
+
+    impl Plugin for CountingPlugin {
+        fn build(&self, app: &mut App) {
+            app.world.init_resource::<Spawned>().0 += 1;
+        }
+    }
+
+    fn fallback(world: &mut World) {
+        world.spawn(());
+        world.init_resource::<Spawned>().0 += 10;
+    }
+
+    #[test]
+    fn test_app_builds_plugins_once_and_runs() {
+        let mut app = App::new();
+        app.add_plugin(CountingPlugin)
+            .add_plugin(CountingPlugin)
+            .add_plugin(PhysicsPlugin::default())
+            .add_plugin(ScenePlugin::new("scenes/missing.ron").with_fallback(fallback));
+        assert!(app.has_plugin::<PhysicsPlugin>());
+        assert_eq!(app.world.resource::<Spawned>().map(|s| s.0), Some(11));
+        assert_eq!(app.world.entity_count(), 1);
+        assert_eq!(
+            app.scheduler.system_names(),
+            vec!["TransformSystem", "VelocitySyncSystem", "PhysicsSystem", "MovementSystem"]
+        );
+
+        app.set_runner(|app| {
+            for _ in 0..3 {
+                app.update(1.0 / 60.0);
+            }
+        });
+        app.run().unwrap();
+        assert_eq!(app.world.resource::<crate::resources::Time>().map(|time| time.frame_count), Some(3));
+    }
+}
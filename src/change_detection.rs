@@ -0,0 +1,112 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use crate::entity::Entity;
+use crate::events::{Event, EventReader};
+use crate::storage::Component;
+use crate::world::World;
+
+/// Monotonic counter the world advances after every system run.
+/// Components remember the tick they were added and last changed at.
+pub type Tick = u64;
+
+/// When a component was added and last changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: Tick,
+    pub changed: Tick,
+}
+
+impl ComponentTicks {
+    /// Ticks of a component inserted at `tick`
+    pub fn new(tick: Tick) -> Self {
+        Self { added: tick, changed: tick }
+    }
+
+    /// Whether the component was added after `last_run`
+    pub fn is_added(&self, last_run: Tick) -> bool {
+        self.added > last_run
+    }
+
+    /// Whether the component was added or changed after `last_run`
+    pub fn is_changed(&self, last_run: Tick) -> bool {
+        self.changed > last_run
+    }
+}
+
+/// Mutable component borrow yielded by queries.
+///
+/// Marks the component as changed the first time it is mutably dereferenced,
+/// so reading through a `&mut T` query does not count as a change.
+pub struct Mut<'w, T> {
+    value: &'w mut T,
+    changed: &'w mut Tick,
+    tick: Tick,
+}
+
+impl<'w, T> Mut<'w, T> {
+    pub(crate) fn new(value: &'w mut T, changed: &'w mut Tick, tick: Tick) -> Self {
+        Self { value, changed, tick }
+    }
+
+    /// Mutate without marking the component as changed
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+
+    /// Convert into a plain reference, marking the component as changed
+    pub fn into_inner(self) -> &'w mut T {
+        *self.changed = self.tick;
+        self.value
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        *self.changed = self.tick;
+        self.value
+    }
+}
+
+/// Removal record kept by every component storage
+pub(crate) struct Removed(pub(crate) Entity);
+
+impl Event for Removed {}
+
+/// Reader for the entities that lost a component of type `T`.
+///
+/// Removals stay readable for two frames like regular events, and each
+/// reader keeps its own cursor.
+pub struct RemovedComponents<T: Component> {
+    reader: EventReader<Removed>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> RemovedComponents<T> {
+    /// Create a reader that sees every removal still buffered
+    pub fn new() -> Self {
+        Self {
+            reader: EventReader::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Entities whose `T` was removed (or despawned) since this reader last read
+    pub fn read<'a>(&mut self, world: &'a World) -> impl Iterator<Item = Entity> + use<'a, T> {
+        let removed = world.storage::<T>().map(|storage| self.reader.read(storage.removed()));
+        removed.into_iter().flatten().map(|removed| removed.0)
+    }
+}
+
+impl<T: Component> Default for RemovedComponents<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
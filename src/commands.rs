@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+use rapier2d::prelude::RigidBodyType;
+use crate::components::{PhysicsBodyOptions, Position};
+use crate::entity::{Entity, EntityReserver};
+use crate::storage::{Bundle, Component};
+use crate::world::World;
+
+/// A deferred world mutation
+pub type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Queue of deferred commands shared between a world and its `Commands` handles
+pub type CommandQueue = Arc<Mutex<Vec<Command>>>;
+
+/// Records structural changes (spawn, despawn, insert, remove, physics bodies)
+/// to apply later, so systems can make them while iterating a query.
+///
+/// Get one with `world.commands()`. The `Scheduler` applies the queue after
+/// each system; call `world.apply_commands()` to apply it by hand.
+pub struct Commands {
+    queue: CommandQueue,
+    reserver: Arc<EntityReserver>,
+}
+
+impl Commands {
+    pub(crate) fn new(queue: CommandQueue, reserver: Arc<EntityReserver>) -> Self {
+        Self { queue, reserver }
+    }
+
+    /// Queue an arbitrary world mutation
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.lock().unwrap().push(Box::new(command));
+    }
+
+    /// Spawn an entity with a bundle of components.
+    ///
+    /// The handle is valid right away but the entity only becomes live when
+    /// the commands are applied.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_> {
+        let entity = self.reserver.reserve();
+        self.add(move |world| world.insert_bundle(entity, bundle));
+        EntityCommands { entity, commands: self }
+    }
+
+    /// Spawn an entity without components
+    pub fn spawn_empty(&mut self) -> EntityCommands<'_> {
+        self.spawn(())
+    }
+
+    /// Queue commands for an existing entity
+    pub fn entity(&mut self, entity: Entity) -> EntityCommands<'_> {
+        EntityCommands { entity, commands: self }
+    }
+
+    /// Despawn an entity, removing its physics body and components
+    pub fn despawn(&mut self, entity: Entity) {
+        self.add(move |world| world.remove_entity(entity));
+    }
+}
+
+/// Commands targeting a single entity
+pub struct EntityCommands<'a> {
+    entity: Entity,
+    commands: &'a mut Commands,
+}
+
+impl EntityCommands<'_> {
+    /// The entity these commands target
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    /// Insert a component, replacing any previous value
+    pub fn insert<T: Component>(&mut self, component: T) -> &mut Self {
+        let entity = self.entity;
+        self.commands.add(move |world| world.insert(entity, component));
+        self
+    }
+
+    /// Remove a component
+    pub fn remove<T: Component>(&mut self) -> &mut Self {
+        let entity = self.entity;
+        self.commands.add(move |world| {
+            world.remove::<T>(entity);
+        });
+        self
+    }
+
+    /// Create a Rapier body and collider for the entity
+    pub fn add_physics_body(&mut self, position: Position, size: f32, body_type: RigidBodyType) -> &mut Self {
+        let entity = self.entity;
+        self.commands.add(move |world| world.add_physics_body(entity, position, size, body_type));
+        self
+    }
+
+    /// Create a Rapier body and collider, overriding some `PhysicsConfig` defaults
+    pub fn add_physics_body_with_options(
+        &mut self,
+        position: Position,
+        size: f32,
+        body_type: RigidBodyType,
+        options: PhysicsBodyOptions,
+    ) -> &mut Self {
+        let entity = self.entity;
+        self.commands.add(move |world| world.add_physics_body_with_options(entity, position, size, body_type, options));
+        self
+    }
+
+    /// Despawn the entity
+    pub fn despawn(&mut self) {
+        self.commands.despawn(self.entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::Velocity;
+    use super::*;
+
+    #[test]
+    fn test_commands_apply_while_iterating() {
+        let mut world = World::new();
+        let doomed = world.spawn((Position::new(0.0, 0.0), Velocity::new(1.0, 0.0)));
+        let mut commands = world.commands();
+
+        let mut spawned = None;
+        for (entity, _position) in world.query::<(Entity, &Position)>() {
+            commands.despawn(entity);
+            spawned = Some(commands.spawn((Position::new(5.0, 5.0),)).insert(Velocity::zero()).id());
+        }
+        let spawned = spawned.unwrap();
+        assert!(!world.is_alive(spawned));
+
+        world.apply_commands();
+        assert!(!world.is_alive(doomed));
+        assert!(world.has::<Velocity>(spawned));
+        assert_eq!(world.get::<Position>(spawned).map(|p| p.x), Some(5.0));
+        assert_eq!(world.entity_count(), 1);
+    }
+
+    #[test]
+    fn test_spawning_directly_leaves_command_spawns_pending() {
+        let mut world = World::new();
+        let mut commands = world.commands();
+        let deferred = commands.spawn((Position::new(1.0, 1.0),)).id();
+        let direct = world.spawn((Position::new(2.0, 2.0),));
+
+        assert_ne!(direct, deferred);
+        assert!(world.is_alive(direct));
+        assert!(!world.is_alive(deferred), "not live before its bundle is applied");
+        assert_eq!(world.query::<Entity>().collect::<Vec<_>>(), vec![direct]);
+
+        world.apply_commands();
+        assert_eq!(world.get::<Position>(deferred).map(|p| p.x), Some(1.0));
+        assert_eq!(world.entity_count(), 2);
+    }
+}
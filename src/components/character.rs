@@ -0,0 +1,171 @@
+use std::f32::consts::FRAC_PI_4;
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+use crate::storage::Component;
+
+/// Moves a kinematic physics body by a desired movement, sliding along
+/// walls and slopes instead of being pushed around by the solver.
+///
+/// Needs a `KinematicPositionBased` body. Gameplay or input code sets
+/// `movement` (and calls `jump`) each frame; every physics step moves the
+/// body with Rapier's `KinematicCharacterController` and updates the
+/// `grounded`/`touching_ceiling`/`touching_wall` flags. Up is against
+/// `PhysicsConfig::gravity`, or screen up without gravity.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CharacterController {
+    /// Gap kept between the body and what it touches, in pixels
+    pub offset: f32,
+    /// Steepest slope, in radians, the character can walk up
+    pub max_slope_climb_angle: f32,
+    /// Slopes steeper than this, in radians, make the character slide down
+    pub min_slope_slide_angle: f32,
+    /// Walk up steps, e.g. stairs; off by default as it is costly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autostep: Option<Autostep>,
+    /// Stick to the ground when it is at most this many pixels below, e.g.
+    /// walking down slopes and steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snap_to_ground: Option<f32>,
+    /// Multiplier of `PhysicsConfig::gravity` while in the air
+    pub gravity_scale: f32,
+
+    /// Desired movement in pixels/second, e.g. from input; kept until changed
+    #[serde(skip)]
+    pub movement: Vector2<f32>,
+    /// Speed along up from jumping and falling, in pixels/second
+    #[serde(skip)]
+    pub vertical_speed: f32,
+    /// Standing on something after the last step
+    #[serde(skip)]
+    pub grounded: bool,
+    /// Bumped into something above during the last step
+    #[serde(skip)]
+    pub touching_ceiling: bool,
+    /// Bumped into something too steep to climb during the last step
+    #[serde(skip)]
+    pub touching_wall: bool,
+}
+
+/// How high and over how little floor a `CharacterController` steps up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Autostep {
+    /// Tallest step climbed, in pixels
+    pub max_height: f32,
+    /// Free floor needed on top of the step, in pixels
+    pub min_width: f32,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            offset: 0.5,
+            max_slope_climb_angle: FRAC_PI_4,
+            min_slope_slide_angle: FRAC_PI_4,
+            autostep: None,
+            snap_to_ground: Some(4.0),
+            gravity_scale: 1.0,
+            movement: Vector2::zeros(),
+            vertical_speed: 0.0,
+            grounded: false,
+            touching_ceiling: false,
+            touching_wall: false,
+        }
+    }
+}
+
+impl CharacterController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_autostep(mut self, max_height: f32, min_width: f32) -> Self {
+        self.autostep = Some(Autostep { max_height, min_width });
+        self
+    }
+
+    pub fn with_snap_to_ground(mut self, distance: Option<f32>) -> Self {
+        self.snap_to_ground = distance;
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    /// Launch upwards at `speed` pixels/second
+    pub fn jump(&mut self, speed: f32) {
+        self.vertical_speed = speed;
+        self.grounded = false;
+    }
+}
+
+impl Component for CharacterController {}
+
+#[cfg(test)]
+mod tests {
+    use rapier2d::prelude::RigidBodyType;
+    use crate::components::{ColliderShape, PhysicsBodyOptions, Position};
+    use crate::resources::PhysicsConfig;
+    use crate::world::World;
+    use super::*;
+
+    fn wall(world: &mut World, x: f32, y: f32, half_width: f32, half_height: f32, options: PhysicsBodyOptions) {
+        let entity = world.spawn(());
+        let shape = ColliderShape::cuboid(half_width, half_height);
+        world.add_physics_body_with_shape(entity, Position::new(x, y), shape, RigidBodyType::Fixed, options).unwrap();
+    }
+
+    #[test]
+    fn test_character_walks_jumps_and_stands_on_one_way_platforms() {
+        let mut world = World::new();
+        world.insert_resource(PhysicsConfig::with_gravity(600.0));
+        let solid = PhysicsBodyOptions::new();
+        wall(&mut world, 300.0, 310.0, 250.0, 10.0, solid); // floor, top at 300
+        wall(&mut world, 260.0, 250.0, 10.0, 50.0, solid); // wall, left side at 250
+        wall(&mut world, 100.0, 250.0, 40.0, 5.0, solid); // ceiling, bottom at 255
+        wall(&mut world, 400.0, 250.0, 40.0, 2.0, solid.as_one_way()); // platform, top at 248
+
+        let player = world.spawn((Position::new(200.0, 200.0),));
+        world.add_physics_body(player, Position::new(200.0, 200.0), 16.0, RigidBodyType::KinematicPositionBased);
+        world.insert(player, CharacterController::new());
+        let steps = |world: &mut World, count: usize| {
+            for _ in 0..count {
+                world.step_physics();
+            }
+            let position = *world.get::<Position>(player).unwrap();
+            (position, *world.get::<CharacterController>(player).unwrap())
+        };
+
+        let (position, controller) = steps(&mut world, 60);
+        assert!(controller.grounded && controller.vertical_speed == 0.0);
+        assert!((position.y - 291.5).abs() < 0.5, "rests on the floor, y = {}", position.y);
+
+        world.get_mut::<CharacterController>(player).unwrap().movement = Vector2::new(200.0, 0.0);
+        let (position, controller) = steps(&mut world, 30);
+        assert!(controller.touching_wall && controller.grounded);
+        assert!((position.x - 241.5).abs() < 0.5, "stops at the wall, x = {}", position.x);
+
+        // Jumping under the ceiling bumps into it and falls back down
+        world.insert(player, Position::new(100.0, 291.5));
+        let controller = world.get_mut::<CharacterController>(player).unwrap();
+        controller.movement = Vector2::zeros();
+        controller.jump(400.0);
+        let mut bumped = false;
+        for _ in 0..30 {
+            let (position, controller) = steps(&mut world, 1);
+            bumped |= controller.touching_ceiling;
+            assert!(position.y > 263.0, "stays below the ceiling, y = {}", position.y);
+        }
+        assert!(bumped);
+        assert!(steps(&mut world, 30).1.grounded);
+
+        // Jumping up through a one-way platform lands on top of it
+        world.insert(player, Position::new(400.0, 291.5));
+        world.get_mut::<CharacterController>(player).unwrap().jump(400.0);
+        let (position, controller) = steps(&mut world, 90);
+        assert!(controller.grounded);
+        assert!((position.y - 239.5).abs() < 0.5, "stands on the platform, y = {}", position.y);
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt;
+use nalgebra::{Point2, Vector2};
+use rapier2d::parry::transformation::convex_hull;
+use rapier2d::prelude::{Isometry, SharedShape};
+use serde::{Deserialize, Serialize};
+use crate::components::{AtlasSprite, Texture};
+use crate::storage::Component;
+
+/// Collision shape of a physics body, in pixels around the body's position.
+///
+/// Added next to `PhysicsBody` by `World::add_physics_body_with_shape`;
+/// bodies created from a `size` get `ColliderShape::square(size)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColliderShape {
+    Ball { radius: f32 },
+    Cuboid { half_width: f32, half_height: f32 },
+    /// Upright capsule: a vertical segment `2 * half_height` long, swept by a circle
+    Capsule { half_height: f32, radius: f32 },
+    /// Convex hull of `points`; needs three that are not on one line
+    ConvexPolygon { points: Vec<(f32, f32)> },
+    /// Line strip through `points`, e.g. terrain; best kept on fixed bodies
+    Polyline { points: Vec<(f32, f32)> },
+    /// Triangles indexing into `vertices`, for concave outlines; best kept on fixed bodies
+    TriMesh { vertices: Vec<(f32, f32)>, indices: Vec<[u32; 3]> },
+    /// Several convex shapes moved as one
+    Compound(Vec<ShapePart>),
+}
+
+/// One shape of a `ColliderShape::Compound`, placed relative to the body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapePart {
+    #[serde(default)]
+    pub offset: (f32, f32),
+    /// Radians
+    #[serde(default)]
+    pub rotation: f32,
+    pub shape: ColliderShape,
+}
+
+impl ShapePart {
+    /// `shape`, moved by `offset`
+    pub fn new(offset: (f32, f32), shape: ColliderShape) -> Self {
+        Self { offset, rotation: 0.0, shape }
+    }
+
+    /// Also rotate the shape by `rotation` radians
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+/// Why a `ColliderShape` can't be turned into a collider
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    /// A convex polygon's points are fewer than three or all on one line
+    DegeneratePolygon,
+    /// A polyline with fewer than two points, or a mesh or compound with no parts
+    Empty,
+    /// A mesh triangle refers to a vertex that doesn't exist
+    IndexOutOfBounds { index: u32, vertex_count: usize },
+    /// Polylines and meshes can't be part of a compound
+    NestedComposite,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::DegeneratePolygon => write!(f, "convex polygon needs three points not on one line"),
+            ShapeError::Empty => write!(f, "shape has no points or parts"),
+            ShapeError::IndexOutOfBounds { index, vertex_count } => {
+                write!(f, "mesh index {} is out of bounds for {} vertices", index, vertex_count)
+            }
+            ShapeError::NestedComposite => write!(f, "polylines and meshes can't be part of a compound shape"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// What `ColliderShape::fit_sprite` wraps around a sprite's opaque pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpriteFit {
+    /// The smallest axis-aligned box
+    Box,
+    /// The smallest convex polygon
+    ConvexHull,
+}
+
+impl ColliderShape {
+    pub fn ball(radius: f32) -> Self {
+        ColliderShape::Ball { radius }
+    }
+
+    pub fn cuboid(half_width: f32, half_height: f32) -> Self {
+        ColliderShape::Cuboid { half_width, half_height }
+    }
+
+    /// A square `size` pixels wide, the shape `World::add_physics_body` uses
+    pub fn square(size: f32) -> Self {
+        Self::cuboid(size / 2.0, size / 2.0)
+    }
+
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        ColliderShape::Capsule { half_height, radius }
+    }
+
+    /// Side of the square this shape is, if it is one
+    pub fn square_size(&self) -> Option<f32> {
+        match *self {
+            ColliderShape::Cuboid { half_width, half_height } if half_width == half_height => Some(half_width * 2.0),
+            _ => None,
+        }
+    }
+
+    /// Build the Rapier shape
+    pub fn to_shared_shape(&self) -> Result<SharedShape, ShapeError> {
+        match self {
+            ColliderShape::Ball { radius } => Ok(SharedShape::ball(*radius)),
+            ColliderShape::Cuboid { half_width, half_height } => Ok(SharedShape::cuboid(*half_width, *half_height)),
+            ColliderShape::Capsule { half_height, radius } => Ok(SharedShape::capsule_y(*half_height, *radius)),
+            ColliderShape::ConvexPolygon { points } => {
+                SharedShape::convex_hull(&to_points(points)).ok_or(ShapeError::DegeneratePolygon)
+            }
+            ColliderShape::Polyline { points } => {
+                if points.len() < 2 {
+                    return Err(ShapeError::Empty);
+                }
+                Ok(SharedShape::polyline(to_points(points), None))
+            }
+            ColliderShape::TriMesh { vertices, indices } => {
+                if indices.is_empty() {
+                    return Err(ShapeError::Empty);
+                }
+                if let Some(&index) = indices.iter().flatten().find(|&&index| index as usize >= vertices.len()) {
+                    return Err(ShapeError::IndexOutOfBounds { index, vertex_count: vertices.len() });
+                }
+                Ok(SharedShape::trimesh(to_points(vertices), indices.clone()))
+            }
+            ColliderShape::Compound(parts) => {
+                let mut shapes = Vec::new();
+                flatten_parts(parts, Isometry::identity(), &mut shapes)?;
+                if shapes.is_empty() {
+                    return Err(ShapeError::Empty);
+                }
+                Ok(SharedShape::compound(shapes))
+            }
+        }
+    }
+
+    /// Fit a shape around the opaque pixels of `sprite` within `texture`,
+    /// drawn at `scale`. The shape is centered like the sprite, so a fully
+    /// opaque 32x32 sprite fits `ColliderShape::square(32.0 * scale)`.
+    /// Returns `None` if the sprite has no opaque pixels.
+    pub fn fit_sprite(texture: &Texture, sprite: &AtlasSprite, fit: SpriteFit, scale: f32) -> Option<Self> {
+        // Outer corners of the leftmost and rightmost opaque pixel of every row
+        let mut corners = Vec::new();
+        for y in 0..sprite.height {
+            let opaque = |&x: &usize| (texture.get_pixel(sprite.x + x, sprite.y + y) >> 24) & 0xFF > 0;
+            let (Some(left), Some(right)) = ((0..sprite.width).find(opaque), (0..sprite.width).rev().find(opaque)) else {
+                continue;
+            };
+            corners.extend([(left, y), (left, y + 1), (right + 1, y), (right + 1, y + 1)]);
+        }
+        if corners.is_empty() {
+            return None;
+        }
+
+        let center = (sprite.width as f32 / 2.0, sprite.height as f32 / 2.0);
+        let local = |(x, y): (usize, usize)| ((x as f32 - center.0) * scale, (y as f32 - center.1) * scale);
+        match fit {
+            SpriteFit::Box => {
+                let min = corners.iter().fold((usize::MAX, usize::MAX), |min, &(x, y)| (min.0.min(x), min.1.min(y)));
+                let max = corners.iter().fold((0, 0), |max, &(x, y)| (max.0.max(x), max.1.max(y)));
+                let (min, max) = (local(min), local(max));
+                let cuboid = Self::cuboid((max.0 - min.0) / 2.0, (max.1 - min.1) / 2.0);
+                let offset = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+                if offset == (0.0, 0.0) {
+                    Some(cuboid)
+                } else {
+                    Some(ColliderShape::Compound(vec![ShapePart::new(offset, cuboid)]))
+                }
+            }
+            SpriteFit::ConvexHull => {
+                let points: Vec<(f32, f32)> = corners.into_iter().map(local).collect();
+                let hull = convex_hull(&to_points(&points));
+                Some(ColliderShape::ConvexPolygon { points: hull.iter().map(|point| (point.x, point.y)).collect() })
+            }
+        }
+    }
+}
+
+impl Component for ColliderShape {}
+
+fn to_points(points: &[(f32, f32)]) -> Vec<Point2<f32>> {
+    points.iter().map(|&(x, y)| Point2::new(x, y)).collect()
+}
+
+/// Rapier compounds can't nest, so nested compound parts are merged into
+/// their parent with their placements combined
+fn flatten_parts(
+    parts: &[ShapePart],
+    parent: Isometry<f32>,
+    shapes: &mut Vec<(Isometry<f32>, SharedShape)>,
+) -> Result<(), ShapeError> {
+    for part in parts {
+        let placement = parent * Isometry::new(Vector2::new(part.offset.0, part.offset.1), part.rotation);
+        match &part.shape {
+            ColliderShape::Compound(children) => flatten_parts(children, placement, shapes)?,
+            ColliderShape::Polyline { .. } | ColliderShape::TriMesh { .. } => return Err(ShapeError::NestedComposite),
+            shape => shapes.push((placement, shape.to_shared_shape()?)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes_build_and_fit_sprites() {
+        let nested = ColliderShape::Compound(vec![
+            ShapePart::new((10.0, 0.0), ColliderShape::ball(4.0)),
+            ShapePart::new((0.0, 5.0), ColliderShape::Compound(vec![
+                ShapePart::new((1.0, 0.0), ColliderShape::capsule(2.0, 1.0)),
+            ])),
+        ]);
+        let compound = nested.to_shared_shape().unwrap();
+        assert_eq!(compound.as_compound().map(|compound| compound.shapes().len()), Some(2));
+
+        let line = ColliderShape::Polyline { points: vec![(0.0, 0.0), (10.0, 0.0)] };
+        assert!(line.to_shared_shape().is_ok());
+        let in_compound = ColliderShape::Compound(vec![ShapePart::new((0.0, 0.0), line)]);
+        assert_eq!(in_compound.to_shared_shape().err(), Some(ShapeError::NestedComposite));
+        let collinear = ColliderShape::ConvexPolygon { points: vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)] };
+        assert_eq!(collinear.to_shared_shape().err(), Some(ShapeError::DegeneratePolygon));
+        let mesh = ColliderShape::TriMesh { vertices: vec![(0.0, 0.0), (1.0, 0.0)], indices: vec![[0, 1, 2]] };
+        assert_eq!(mesh.to_shared_shape().err(), Some(ShapeError::IndexOutOfBounds { index: 2, vertex_count: 2 }));
+
+        // An opaque triangle in the right half of an 8x8 sprite at (8, 0)
+        let mut texture = Texture::new(16, 8, vec![0; 16 * 8]);
+        for y in 0..8 {
+            for x in 12..12 + y / 2 + 1 {
+                texture.set_pixel(x, y, 0xFFFFFFFF);
+            }
+        }
+        let sprite = AtlasSprite::new(8, 0, 8, 8);
+        let fitted = ColliderShape::fit_sprite(&texture, &sprite, SpriteFit::Box, 2.0);
+        assert_eq!(fitted, Some(ColliderShape::Compound(vec![ShapePart::new((4.0, 0.0), ColliderShape::cuboid(4.0, 8.0))])));
+        let Some(ColliderShape::ConvexPolygon { points }) = ColliderShape::fit_sprite(&texture, &sprite, SpriteFit::ConvexHull, 1.0) else {
+            panic!("expected a hull");
+        };
+        for corner in [(0.0, -4.0), (1.0, -4.0), (4.0, 4.0), (0.0, 4.0)] {
+            assert!(points.contains(&corner), "{:?} in {:?}", corner, points);
+        }
+        let empty = AtlasSprite::new(0, 0, 4, 4);
+        assert_eq!(ColliderShape::fit_sprite(&texture, &empty, SpriteFit::Box, 1.0), None);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::entity::Entity;
+use crate::storage::Component;
+
+/// Parent of an entity. Managed by `World::set_parent` / `World::remove_parent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub(crate) Entity);
+
+impl Parent {
+    /// The parent entity
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+impl Component for Parent {}
+
+/// Children of an entity, in the order they were attached.
+/// Managed by `World::set_parent` / `World::remove_parent`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(pub(crate) Vec<Entity>);
+
+impl Children {
+    /// Child entities as a slice
+    pub fn as_slice(&self) -> &[Entity] {
+        &self.0
+    }
+
+    /// Iterate over the child entities
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Number of children
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no children
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Component for Children {}
@@ -5,6 +5,13 @@ pub mod sprite;
 pub mod texture_sprite;
 pub mod texture;
 pub mod atlas;
+pub mod physics_body;
+pub mod collider;
+pub mod character;
+pub mod transform;
+pub mod hierarchy;
+pub mod name;
+pub mod tags;
 
 // Re-export all components for easy access
 pub use position::Position;
@@ -13,6 +20,13 @@ pub use sprite::Sprite;
 pub use texture_sprite::TextureSprite;
 pub use texture::{Texture, AtlasSprite, SpriteAtlas};
 pub use atlas::AssetsLoader;
+pub use physics_body::{PhysicsBody, PhysicsBodyOptions, PhysicsInterpolation, PhysicsMaterial};
+pub use collider::{ColliderShape, ShapeError, ShapePart, SpriteFit};
+pub use character::{Autostep, CharacterController};
+pub use transform::{Transform, GlobalTransform};
+pub use hierarchy::{Parent, Children};
+pub use name::Name;
+pub use tags::Tags;
 
-// Entity type definition
-pub type Entity = u32;
+// Entity handle lives in the crate root; re-exported here for existing imports
+pub use crate::entity::Entity;
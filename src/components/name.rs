@@ -0,0 +1,29 @@
+use std::fmt;
+use crate::storage::Component;
+
+/// Human-readable entity name, indexed by the world for `World::find_by_name`.
+///
+/// Rename with `World::set_name` (or insert a new `Name`) so the index stays
+/// in sync; names do not have to be unique.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name(String);
+
+impl Name {
+    /// Create a new name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// The name as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Component for Name {}
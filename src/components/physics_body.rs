@@ -0,0 +1,219 @@
+use nalgebra::Vector2;
+use rapier2d::prelude::{InteractionGroups, Isometry, RigidBodyHandle};
+use serde::{Deserialize, Serialize};
+use crate::storage::Component;
+
+/// Marks an entity driven by a Rapier rigid body.
+///
+/// Added by `World::add_physics_body`; the physics step owns the entity's
+/// `Position` and `Velocity`, so integrators should skip it (`Without<PhysicsBody>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhysicsBody {
+    pub handle: RigidBodyHandle,
+}
+
+impl PhysicsBody {
+    /// Create a marker for a rigid body
+    pub fn new(handle: RigidBodyHandle) -> Self {
+        Self { handle }
+    }
+}
+
+impl Component for PhysicsBody {}
+
+/// Rigid body poses from the last two physics steps.
+///
+/// Physics runs in fixed steps that rarely line up with frames, so drawing
+/// the latest step makes motion stutter; renderers blend the two poses by
+/// `FixedTime::alpha` instead. Kept up to date by the physics sync, and
+/// snapped to the new pose when gameplay code moves the body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsInterpolation {
+    pub previous: Isometry<f32>,
+    pub current: Isometry<f32>,
+}
+
+impl PhysicsInterpolation {
+    /// Start at rest at `pose`
+    pub fn new(pose: Isometry<f32>) -> Self {
+        Self { previous: pose, current: pose }
+    }
+
+    /// Record the pose after a physics step
+    pub fn push(&mut self, pose: Isometry<f32>) {
+        self.previous = self.current;
+        self.current = pose;
+    }
+
+    /// Jump to `pose` without blending from the old one
+    pub fn snap(&mut self, pose: Isometry<f32>) {
+        *self = Self::new(pose);
+    }
+
+    /// Pose `alpha` of the way from the previous step to the current one
+    pub fn interpolate(&self, alpha: f32) -> Isometry<f32> {
+        self.previous.lerp_slerp(&self.current, alpha)
+    }
+
+    /// Translation `alpha` of the way from the previous step to the current one
+    pub fn translation(&self, alpha: f32) -> Vector2<f32> {
+        self.previous.translation.vector.lerp(&self.current.translation.vector, alpha)
+    }
+}
+
+impl Component for PhysicsInterpolation {}
+
+/// Surface and density of a collider
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsMaterial {
+    /// Bounciness, 0 (none) to 1 (perfectly elastic); the higher of two touching colliders wins
+    pub restitution: f32,
+    /// Friction coefficient, averaged between touching colliders
+    pub friction: f32,
+    pub density: f32,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self { restitution: 0.9, friction: 0.2, density: 1.0 }
+    }
+}
+
+/// Per-body overrides of the `PhysicsConfig` defaults.
+///
+/// Given to `World::add_physics_body_with_options` or set in a scene's
+/// `PhysicsBodyData`; kept on the entity when not all defaults, so the
+/// editor can save it back.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PhysicsBodyOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linear_damping: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub angular_damping: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_sleep: Option<bool>,
+    /// Multiplier of `PhysicsConfig::gravity`; 0 makes the body float
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gravity_scale: Option<f32>,
+    /// Keep the body upright, e.g. for platformer characters
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lock_rotation: bool,
+    /// Total mass, replacing the one computed from the material's density
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mass: Option<f32>,
+    /// Continuous collision detection for fast bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ccd: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<PhysicsMaterial>,
+    /// Detect overlaps without colliding, e.g. for pickups and damage zones;
+    /// sends `SensorEntered`/`SensorExited` instead of collision events
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensor: bool,
+    /// Let `CharacterController`s pass through from below and stand on top
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub one_way: bool,
+    /// Let the body leave the `WorldBounds`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_bounds: bool,
+    /// Send `ContactForce` events while touching colliders push on this one
+    /// harder than this summed force; none are sent without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_force_threshold: Option<f32>,
+    /// Which colliders this one touches at all: contacts, events, sensor
+    /// overlaps and spatial queries. Scenes name these by layer, see
+    /// `PhysicsBodyData::collision_layers`
+    #[serde(skip)]
+    pub collision_groups: Option<InteractionGroups>,
+    /// Which touching colliders also push this one apart; others still
+    /// send collision events but pass through
+    #[serde(skip)]
+    pub solver_groups: Option<InteractionGroups>,
+}
+
+impl PhysicsBodyOptions {
+    /// No overrides
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether nothing is overridden
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn with_linear_damping(mut self, damping: f32) -> Self {
+        self.linear_damping = Some(damping);
+        self
+    }
+
+    pub fn with_angular_damping(mut self, damping: f32) -> Self {
+        self.angular_damping = Some(damping);
+        self
+    }
+
+    pub fn with_can_sleep(mut self, can_sleep: bool) -> Self {
+        self.can_sleep = Some(can_sleep);
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = Some(scale);
+        self
+    }
+
+    pub fn with_locked_rotation(mut self) -> Self {
+        self.lock_rotation = true;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = Some(mass);
+        self
+    }
+
+    pub fn with_ccd(mut self, enabled: bool) -> Self {
+        self.ccd = Some(enabled);
+        self
+    }
+
+    pub fn with_material(mut self, material: PhysicsMaterial) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn as_sensor(mut self) -> Self {
+        self.sensor = true;
+        self
+    }
+
+    pub fn as_one_way(mut self) -> Self {
+        self.one_way = true;
+        self
+    }
+
+    pub fn ignoring_bounds(mut self) -> Self {
+        self.ignore_bounds = true;
+        self
+    }
+
+    /// E.g. `with_contact_force_events(5000.0)` to only hear about hard impacts
+    pub fn with_contact_force_events(mut self, threshold: f32) -> Self {
+        self.contact_force_threshold = Some(threshold);
+        self
+    }
+
+    /// E.g. `with_collision_groups(layers.groups(&["player_bullet"], &["enemy"])?)`
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = Some(groups);
+        self
+    }
+
+    pub fn with_solver_groups(mut self, groups: InteractionGroups) -> Self {
+        self.solver_groups = Some(groups);
+        self
+    }
+}
+
+impl Component for PhysicsBodyOptions {}
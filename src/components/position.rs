@@ -1,3 +1,5 @@
+use crate::storage::Component;
+
 /// Position component for entities in 2D space
 #[derive(Debug, Clone, Copy)]
 pub struct Position {
@@ -23,3 +25,5 @@ impl Position {
         (dx * dx + dy * dy).sqrt()
     }
 }
+
+impl Component for Position {}
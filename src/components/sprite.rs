@@ -1,3 +1,5 @@
+use crate::storage::Component;
+
 /// Basic sprite component with color and size
 #[derive(Debug, Clone, Copy)]
 pub struct Sprite {
@@ -45,3 +47,5 @@ impl Sprite {
         (a, r, g, b)
     }
 }
+
+impl Component for Sprite {}
@@ -0,0 +1,46 @@
+use std::collections::BTreeSet;
+use crate::storage::Component;
+
+/// Set of string tags, indexed by the world for `World::iter_tagged`.
+///
+/// Change tags with `World::add_tag` / `World::remove_tag` (or insert a new
+/// `Tags`) so the index stays in sync.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tags(BTreeSet<String>);
+
+impl Tags {
+    /// Create a tag set
+    pub fn new<S: Into<String>>(tags: impl IntoIterator<Item = S>) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether a tag is present
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+
+    /// Iterate over the tags in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Number of tags
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no tags
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, tag: String) -> bool {
+        self.0.insert(tag)
+    }
+
+    pub(crate) fn remove(&mut self, tag: &str) -> bool {
+        self.0.remove(tag)
+    }
+}
+
+impl Component for Tags {}
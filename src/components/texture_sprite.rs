@@ -1,3 +1,5 @@
+use crate::storage::Component;
+
 /// Texture sprite component that references sprites from an atlas
 #[derive(Debug, Clone)]
 pub struct TextureSprite {
@@ -29,3 +31,5 @@ impl TextureSprite {
         )
     }
 }
+
+impl Component for TextureSprite {}
@@ -0,0 +1,122 @@
+use nalgebra::{Rotation2, Vector2};
+use crate::storage::Component;
+
+/// Local transform of an entity, relative to its parent (or to the world
+/// for root entities).
+///
+/// For root entities with a `Position`, the `Position` stays the source of
+/// truth and is copied into `translation` during propagation. For children,
+/// propagation writes the resulting world position back into `Position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vector2<f32>,
+    /// Rotation in radians
+    pub rotation: f32,
+    pub scale: Vector2<f32>,
+}
+
+impl Transform {
+    /// Identity transform
+    pub const IDENTITY: Self = Self {
+        translation: Vector2::new(0.0, 0.0),
+        rotation: 0.0,
+        scale: Vector2::new(1.0, 1.0),
+    };
+
+    /// Transform with only a translation
+    pub fn from_xy(x: f32, y: f32) -> Self {
+        Self {
+            translation: Vector2::new(x, y),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Set the rotation in radians
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Set the scale
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vector2::new(x, y);
+        self
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Component for Transform {}
+
+/// World-space transform computed by transform propagation. Do not edit;
+/// change the local `Transform` (or a root's `Position`) instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlobalTransform {
+    pub translation: Vector2<f32>,
+    /// Rotation in radians
+    pub rotation: f32,
+    pub scale: Vector2<f32>,
+}
+
+impl GlobalTransform {
+    /// Combine this (parent) transform with a child's local transform
+    pub fn mul_transform(&self, local: &Transform) -> Self {
+        Self {
+            translation: self.transform_point(local.translation),
+            rotation: self.rotation + local.rotation,
+            scale: self.scale.component_mul(&local.scale),
+        }
+    }
+
+    /// Map a point from this transform's local space to world space
+    pub fn transform_point(&self, point: Vector2<f32>) -> Vector2<f32> {
+        self.translation + Rotation2::new(self.rotation) * self.scale.component_mul(&point)
+    }
+
+    /// Map a world-space point into this transform's local space
+    pub fn inverse_transform_point(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let unrotated = Rotation2::new(-self.rotation) * (point - self.translation);
+        unrotated.component_div(&self.scale)
+    }
+
+    /// This world transform expressed as a local transform of `parent`
+    pub fn reparented_to(&self, parent: &GlobalTransform) -> Transform {
+        Transform {
+            translation: parent.inverse_transform_point(self.translation),
+            rotation: self.rotation - parent.rotation,
+            scale: self.scale.component_div(&parent.scale),
+        }
+    }
+}
+
+impl From<Transform> for GlobalTransform {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            scale: transform.scale,
+        }
+    }
+}
+
+impl From<GlobalTransform> for Transform {
+    fn from(transform: GlobalTransform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            scale: transform.scale,
+        }
+    }
+}
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Transform::IDENTITY.into()
+    }
+}
+
+impl Component for GlobalTransform {}
@@ -1,3 +1,5 @@
+use crate::storage::Component;
+
 /// Velocity component for entities in 2D space
 #[derive(Debug, Clone, Copy)]
 pub struct Velocity {
@@ -39,3 +41,5 @@ impl Velocity {
         }
     }
 }
+
+impl Component for Velocity {}
@@ -2,10 +2,18 @@ use eframe::egui;
 use std::collections::HashMap;
 use std::time::Instant;
 use crate::components::*;
+use crate::resources::{Resource, WorldBounds};
 use crate::world::World;
-use crate::scene::{Scene, SceneLoader, EntityData, PhysicsBodyData, PhysicsBodyType};
+use crate::app::App;
+use crate::layers::CollisionLayers;
+use crate::project::ProjectConfig;
+use crate::plugins::{PhysicsPlugin, RenderPlugin};
+use crate::scene::{Scene, SceneLoader};
 use crate::systems::*;
 
+/// Frames the toolbar profiler averages over
+const PROFILER_WINDOW: usize = 120;
+
 /// Editor state and application
 pub struct EditorApp {
     /// The game world
@@ -51,8 +59,6 @@ pub struct ComponentEditorState {
     show_add_component: bool,
     /// Available component types
     available_components: Vec<ComponentType>,
-    /// Component being edited
-    editing_component: Option<ComponentType>,
 }
 
 /// Available component types that can be added to entities
@@ -72,29 +78,19 @@ pub struct AssetManager {
     drag_state: DragState,
 }
 
-/// Entity hierarchy management
+/// Entity hierarchy management (parent/child links live in the world)
 #[derive(Default)]
 pub struct EntityHierarchy {
-    /// Parent-child relationships
-    parent_child_map: HashMap<Entity, Vec<Entity>>,
     /// Root entities (no parent)
     root_entities: Vec<Entity>,
-    /// Expanded state in UI
-    expanded_entities: HashMap<Entity, bool>,
 }
 
 /// Grid-based level editor
 pub struct GridEditor {
-    /// Grid size (cells per side)
-    grid_size: usize,
     /// Cell size in pixels
     cell_size: f32,
-    /// Grid offset for panning
-    grid_offset: egui::Vec2,
     /// Entities placed on grid
     grid_entities: HashMap<(i32, i32), Entity>,
-    /// Currently dragging entity
-    dragging_entity: Option<DraggedEntity>,
 }
 
 /// Drag and drop state
@@ -136,19 +132,17 @@ impl Default for GridSettings {
     }
 }
 
-/// Game state management
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Game state management; also inserted into the world as a resource so
+/// systems can `run_if` on it
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum GameState {
+    #[default]
     Stopped,
     Playing,
     Paused,
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::Stopped
-    }
-}
+impl Resource for GameState {}
 
 impl Default for AssetManager {
     fn default() -> Self {
@@ -174,7 +168,6 @@ impl Default for ComponentEditorState {
                 ComponentType::TextureSprite,
                 ComponentType::PhysicsBody,
             ],
-            editing_component: None,
         }
     }
 }
@@ -182,11 +175,8 @@ impl Default for ComponentEditorState {
 impl Default for GridEditor {
     fn default() -> Self {
         Self {
-            grid_size: 20,
             cell_size: 40.0,
-            grid_offset: egui::Vec2::ZERO,
             grid_entities: HashMap::new(),
-            dragging_entity: None,
         }
     }
 }
@@ -194,32 +184,20 @@ impl Default for GridEditor {
 impl EditorApp {
     /// Create a new editor application
     pub fn new() -> Self {
-        let mut world = World::new();
-        
-        // Load sprite atlas
-        let atlas = match AssetsLoader::load_png("assets/sprites/atlas.png") {
-            Ok(texture) => {
-                println!("Successfully loaded PNG atlas!");
-                let mut atlas = SpriteAtlas::new(texture);
-                atlas.add_sprite("player".to_string(), 0, 0, 32, 32);
-                atlas.add_sprite("enemy1".to_string(), 32, 0, 32, 32);
-                atlas.add_sprite("enemy2".to_string(), 64, 0, 32, 32);
-                atlas.add_sprite("powerup".to_string(), 96, 0, 32, 32);
-                atlas
-            }
-            Err(_) => {
-                println!("Could not load PNG atlas, using sample sprites");
-                AssetsLoader::create_sample_atlas()
-            }
-        };
-        
-        world.set_sprite_atlas(atlas);
-
-        // Setup systems scheduler for game simulation
-        let mut scheduler = Scheduler::new();
-        scheduler.add_system(Box::new(VelocitySyncSystem::new()));
-        scheduler.add_system(Box::new(PhysicsSystem::new()));
-        scheduler.add_query_system(MovementSystem::new());
+        // Same setup as the game: the atlas plus physics, which only runs
+        // in play mode; diagnostics keep updating while editing
+        let project = ProjectConfig::load("project.ron").unwrap_or_else(|e| {
+            println!("Could not load project.ron: {}", e);
+            ProjectConfig::default()
+        });
+        let physics = PhysicsPlugin::default()
+            .with_layers(project.collision_layers)
+            .run_if(resource_equals(GameState::Playing));
+        let mut app = App::new();
+        app.add_plugin(RenderPlugin::default().headless())
+            .add_plugin(physics);
+        app.add_system(Box::new(DiagnosticsSystem::new()));
+        let (world, scheduler) = app.into_parts();
 
         Self {
             world,
@@ -246,10 +224,12 @@ impl EditorApp {
         self.world.add_position(entity, Position::new(world_x, world_y));
         self.world.add_velocity(entity, Velocity::new(
             // Add small velocity for interesting physics
-            ((entity % 3) as f32 - 1.0) * 10.0,
-            ((entity % 5) as f32 - 2.0) * 10.0
+            ((entity.index() % 3) as f32 - 1.0) * 10.0,
+            ((entity.index() % 5) as f32 - 2.0) * 10.0
         ));
         self.world.add_texture_sprite(entity, TextureSprite::with_name(sprite_name));
+        let name = self.unused_name(sprite_name);
+        self.world.set_name(entity, name);
         
         // Add physics body for most entities
         self.world.add_physics_body(
@@ -271,17 +251,24 @@ impl EditorApp {
         entity
     }
 
+    /// `base`, or `base_2`, `base_3`, ... if that name is taken
+    fn unused_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 2;
+        while self.world.find_by_name(&name).is_some() {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
     /// Load a scene into the editor
     pub fn load_scene(&mut self, scene_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         // Clear current state
         self.clear_scene();
 
         // Load scene
-        let scene = if scene_path.ends_with(".ron") {
-            SceneLoader::load_from_ron(scene_path)?
-        } else {
-            SceneLoader::load_from_json(scene_path)?
-        };
+        let scene = SceneLoader::load(scene_path)?;
 
         // Spawn entities
         let entity_map = SceneLoader::spawn_scene(&scene, &mut self.world);
@@ -311,7 +298,6 @@ impl EditorApp {
         // Remove all entities except keep world structure
         self.grid_editor.grid_entities.clear();
         self.hierarchy.root_entities.clear();
-        self.hierarchy.parent_child_map.clear();
         self.editor_state.selected_entity = None;
         
         // Create a new world to clear everything
@@ -319,6 +305,13 @@ impl EditorApp {
         if let Some(atlas) = self.world.sprite_atlas.take() {
             new_world.set_sprite_atlas(atlas);
         }
+        if let Some(layers) = self.world.remove_resource::<CollisionLayers>() {
+            new_world.insert_resource(layers);
+        }
+        match self.world.remove_resource::<WorldBounds>() {
+            Some(bounds) => new_world.insert_resource(bounds),
+            None => new_world.remove_resource::<WorldBounds>(),
+        };
         self.world = new_world;
     }
 
@@ -329,7 +322,12 @@ impl EditorApp {
         let mut grid_x = 0;
         let mut grid_y = 0;
         
-        for (_name, &entity) in entity_map {
+        for &entity in entity_map.values() {
+            // Children follow their parent and are listed under it
+            if self.world.parent(entity).is_some() {
+                continue;
+            }
+
             // Add to grid at current position
             self.grid_editor.grid_entities.insert((grid_x, grid_y), entity);
             
@@ -347,88 +345,34 @@ impl EditorApp {
 
     /// Create scene from current editor state
     fn create_scene_from_editor(&self) -> Scene {
-        let mut entities = Vec::new();
-
-        // Convert grid entities to scene entities
-        for ((grid_x, grid_y), &entity) in &self.grid_editor.grid_entities {
-            if let Some(entity_data) = self.create_entity_data(entity, Some((*grid_x, *grid_y))) {
-                entities.push(entity_data);
-            }
-        }
-
-        Scene {
-            name: "Editor Scene".to_string(),
-            description: Some("Scene created from editor".to_string()),
-            entities,
-        }
-    }
-
-    /// Create entity data from world entity
-    fn create_entity_data(&self, entity: Entity, grid_pos: Option<(i32, i32)>) -> Option<EntityData> {
-        let position = self.world.get_position(entity).copied();
-        let velocity = self.world.get_velocity(entity).copied();
-        let texture_sprite = self.world.get_texture_sprite(entity).cloned();
-
-        // Create physics body data if entity has physics
-        let physics_body = if self.world.entity_to_body.contains_key(&entity) {
-            Some(PhysicsBodyData {
-                size: self.editor_state.grid_settings.grid_size / 2.0,
-                body_type: PhysicsBodyType::Dynamic,
-            })
-        } else {
-            None
-        };
-
-        // Generate entity name from grid position or entity ID
-        let name = if let Some((x, y)) = grid_pos {
-            Some(format!("entity_{}_{}", x, y))
-        } else {
-            Some(format!("entity_{}", entity))
-        };
-
-        Some(EntityData {
-            name,
-            position,
-            velocity,
-            texture_sprite,
-            physics_body,
-        })
-    }
-
-    /// Get world position from grid coordinates
-    fn grid_to_world(&self, grid_x: i32, grid_y: i32) -> (f32, f32) {
-        (
-            grid_x as f32 * self.editor_state.grid_settings.grid_size,
-            grid_y as f32 * self.editor_state.grid_settings.grid_size,
+        SceneLoader::create_scene_from_world(
+            &self.world,
+            "Editor Scene".to_string(),
+            Some("Scene created from editor".to_string()),
         )
     }
+}
 
-    /// Get grid coordinates from world position
-    fn world_to_grid(&self, world_x: f32, world_y: f32) -> (i32, i32) {
-        (
-            (world_x / self.editor_state.grid_settings.grid_size).round() as i32,
-            (world_y / self.editor_state.grid_settings.grid_size).round() as i32,
-        )
+impl Default for EditorApp {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl eframe::App for EditorApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Update game simulation if playing
+        // Calculate delta time
+        let current_time = Instant::now();
+        let dt = current_time.duration_since(self.last_time).as_secs_f32();
+        self.last_time = current_time;
+
+        // Run systems scheduler; gameplay systems only run while playing
+        self.world.insert_resource(self.game_state);
+        self.scheduler.update(&mut self.world, dt);
+
         if self.game_state == GameState::Playing {
-            // Calculate delta time
-            let current_time = Instant::now();
-            let dt = current_time.duration_since(self.last_time).as_secs_f32();
-            self.last_time = current_time;
-            
-            // Run systems scheduler (includes physics, movement, etc.)
-            self.scheduler.update(&mut self.world, dt);
-            
             // Request repaint for smooth animation
             ctx.request_repaint();
-        } else {
-            // Reset timer when not playing to avoid large dt when resuming
-            self.last_time = Instant::now();
         }
 
         // Main menu bar
@@ -481,10 +425,10 @@ impl EditorApp {
                     }
                     
                     if ui.button("Save Scene").clicked() {
-                        if let Some(ref path) = self.current_scene_path.clone() {
-                            if let Err(e) = self.save_scene(path) {
-                                eprintln!("Failed to save scene: {}", e);
-                            }
+                        if let Some(ref path) = self.current_scene_path.clone()
+                            && let Err(e) = self.save_scene(path)
+                        {
+                            eprintln!("Failed to save scene: {}", e);
                         }
                         ui.close_menu();
                     }
@@ -530,7 +474,7 @@ impl EditorApp {
         ui.separator();
 
         for sprite_name in &self.asset_manager.available_sprites.clone() {
-            let button = ui.button(&format!("📦 {}", sprite_name));
+            let button = ui.button(format!("📦 {}", sprite_name));
             
             if button.hovered() {
                 ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Grab);
@@ -572,16 +516,19 @@ impl EditorApp {
         let indent = "  ".repeat(depth);
         
         // Build entity display name with components
-        let mut entity_name = format!("{}Entity {}", indent, entity);
+        let mut entity_name = match self.world.name(entity) {
+            Some(name) => format!("{}{}", indent, name),
+            None => format!("{}Entity {}", indent, entity),
+        };
         let mut components_info = Vec::new();
         
-        if self.world.positions.contains_key(&entity) {
+        if self.world.has::<Position>(entity) {
             components_info.push("📍");
         }
-        if self.world.velocities.contains_key(&entity) {
+        if self.world.has::<Velocity>(entity) {
             components_info.push("🏃");
         }
-        if self.world.texture_sprites.contains_key(&entity) {
+        if self.world.has::<TextureSprite>(entity) {
             components_info.push("🖼️");
         }
         if self.world.entity_to_body.contains_key(&entity) {
@@ -601,12 +548,9 @@ impl EditorApp {
             self.editor_state.show_properties = true;
         }
 
-        // Draw children if expanded
-        let children = self.hierarchy.parent_child_map.get(&entity).cloned();
-        if let Some(children) = children {
-            for child in children {
-                self.draw_entity_tree_node(ui, child, depth + 1);
-            }
+        // Draw children
+        for child in self.world.children(entity).to_vec() {
+            self.draw_entity_tree_node(ui, child, depth + 1);
         }
     }
 
@@ -658,18 +602,69 @@ impl EditorApp {
 
             // Entity count
             let entity_count = self.hierarchy.root_entities.len();
-            ui.label(&format!("Entities: {}", entity_count));
+            ui.label(format!("Entities: {}", entity_count));
+
+            // Debug overlay, updated even while the game is stopped
+            if let Some(diagnostics) = self.world.resource::<Diagnostics>() {
+                ui.label(format!("FPS: {:.0}", diagnostics.fps))
+                    .on_hover_text(format!(
+                        "{:.2} ms frame, {} entities, {} bodies",
+                        diagnostics.frame_time * 1e3, diagnostics.entity_count, diagnostics.body_count
+                    ));
+            }
 
             ui.separator();
 
             // Current scene info
             let scene_text = if let Some(ref path) = self.current_scene_path {
-                format!("📄 {}", path.split('/').last().unwrap_or(path))
+                format!("📄 {}", path.split('/').next_back().unwrap_or(path))
             } else {
                 "📄 Untitled Scene".to_string()
             };
             ui.label(scene_text);
         });
+
+        self.draw_profiler_bar(ui);
+    }
+
+    /// Profiling toggle, the slowest systems and export buttons
+    fn draw_profiler_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut profiling = self.scheduler.profiler().is_some();
+            if ui.checkbox(&mut profiling, "📊 Profile").changed() {
+                if profiling {
+                    self.scheduler.enable_profiling(PROFILER_WINDOW);
+                } else {
+                    self.scheduler.disable_profiling();
+                }
+            }
+
+            let Some(profiler) = self.scheduler.profiler() else {
+                return;
+            };
+
+            let mut stats = profiler.stats();
+            stats.sort_by(|a, b| b.avg_ms.total_cmp(&a.avg_ms));
+            for stats in stats.iter().take(4) {
+                ui.separator();
+                ui.label(format!("{} {:.2} / p99 {:.2} ms", stats.name, stats.avg_ms, stats.p99_ms))
+                    .on_hover_text(format!(
+                        "min {:.3} ms, max {:.3} ms over {} frames",
+                        stats.min_ms, stats.max_ms, stats.samples
+                    ));
+            }
+
+            ui.separator();
+            if ui.button("Export").on_hover_text("Write profile.csv, profile.json and profile_trace.json").clicked() {
+                let saved = profiler.save_csv("profile.csv")
+                    .and_then(|_| profiler.save_json("profile.json"))
+                    .and_then(|_| profiler.save_chrome_trace("profile_trace.json"));
+                match saved {
+                    Ok(()) => println!("Saved profile.csv, profile.json and profile_trace.json"),
+                    Err(e) => eprintln!("Failed to save profile: {}", e),
+                }
+            }
+        });
     }
 
     fn draw_grid_editor(&mut self, ui: &mut egui::Ui) {
@@ -683,24 +678,24 @@ impl EditorApp {
 
         // Handle asset drag and drop
         let dragging_sprite = self.asset_manager.drag_state.dragging_sprite.clone();
-        if let Some(sprite_name) = dragging_sprite {
-            if response.hovered() {
-                ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Grabbing);
+        if let Some(sprite_name) = dragging_sprite
+            && response.hovered()
+        {
+            ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Grabbing);
                 
-                if ui.input(|i| i.pointer.any_released()) {
-                    // Drop asset at current position
-                    if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
-                        let grid_pos = self.screen_to_grid(pos, rect);
+            if ui.input(|i| i.pointer.any_released()) {
+                // Drop asset at current position
+                if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
+                    let grid_pos = self.screen_to_grid(pos, rect);
                         
-                        // Only create if grid cell is empty
-                        if !self.grid_editor.grid_entities.contains_key(&grid_pos) {
-                            self.create_entity_at_grid(grid_pos.0, grid_pos.1, &sprite_name);
-                        }
+                    // Only create if grid cell is empty
+                    if !self.grid_editor.grid_entities.contains_key(&grid_pos) {
+                        self.create_entity_at_grid(grid_pos.0, grid_pos.1, &sprite_name);
                     }
-                    
-                    // Clear drag state
-                    self.asset_manager.drag_state.dragging_sprite = None;
                 }
+                    
+                // Clear drag state
+                self.asset_manager.drag_state.dragging_sprite = None;
             }
         }
 
@@ -716,10 +711,14 @@ impl EditorApp {
             .default_width(350.0)
             .show(ctx, |ui| {
                 if let Some(entity) = self.editor_state.selected_entity {
-                    ui.heading(&format!("Entity {}", entity));
+                    match self.world.name(entity) {
+                        Some(name) => ui.heading(format!("{} ({})", name, entity)),
+                        None => ui.heading(format!("Entity {}", entity)),
+                    };
                     ui.separator();
 
                     // Component sections
+                    self.draw_name_component(ui, entity);
                     self.draw_position_component(ui, entity);
                     self.draw_velocity_component(ui, entity);
                     self.draw_texture_sprite_component(ui, entity);
@@ -750,6 +749,25 @@ impl EditorApp {
             });
     }
 
+    fn draw_name_component(&mut self, ui: &mut egui::Ui, entity: Entity) {
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            let mut name = self.world.name(entity).unwrap_or_default().to_string();
+            if ui.text_edit_singleline(&mut name).changed() {
+                if name.is_empty() {
+                    self.world.remove::<Name>(entity);
+                } else {
+                    self.world.set_name(entity, name);
+                }
+            }
+        });
+        if let Some(tags) = self.world.get::<Tags>(entity)
+            && !tags.is_empty()
+        {
+            ui.label(format!("Tags: {}", tags.iter().collect::<Vec<_>>().join(", ")));
+        }
+    }
+
     fn draw_position_component(&mut self, ui: &mut egui::Ui, entity: Entity) {
         ui.collapsing("📍 Position", |ui| {
             if let Some(position) = self.world.get_position_mut(entity) {
@@ -773,10 +791,10 @@ impl EditorApp {
                     position.y = pos_y;
                     
                     // Update physics body position if it exists
-                    if let Some(&body_handle) = self.world.entity_to_body.get(&entity) {
-                        if let Some(body) = self.world.physics_world.get_mut(body_handle) {
-                            body.set_translation(nalgebra::Vector2::new(pos_x, pos_y), true);
-                        }
+                    if let Some(&body_handle) = self.world.entity_to_body.get(&entity)
+                        && let Some(body) = self.world.physics_world.get_mut(body_handle)
+                    {
+                        body.set_translation(nalgebra::Vector2::new(pos_x, pos_y), true);
                     }
                 }
             } else {
@@ -800,7 +818,7 @@ impl EditorApp {
                 });
                 
                 ui.horizontal(|ui| {
-                    ui.label(&format!("Speed: {:.2}", velocity.magnitude()));
+                    ui.label(format!("Speed: {:.2}", velocity.magnitude()));
                     if ui.button("Reset").clicked() {
                         velocity.x = 0.0;
                         velocity.y = 0.0;
@@ -818,7 +836,7 @@ impl EditorApp {
 
     fn draw_texture_sprite_component(&mut self, ui: &mut egui::Ui, entity: Entity) {
         ui.collapsing("🖼️ Texture Sprite", |ui| {
-            if let Some(texture_sprite) = self.world.texture_sprites.get(&entity).cloned() {
+            if let Some(texture_sprite) = self.world.get::<TextureSprite>(entity).cloned() {
                 ui.horizontal(|ui| {
                     ui.label("Atlas:");
                     ui.label(&texture_sprite.atlas_name);
@@ -829,7 +847,7 @@ impl EditorApp {
                     ui.label("Scale:");
                     if ui.add(egui::DragValue::new(&mut scale).range(0.1..=5.0).speed(0.1)).changed() {
                         let new_sprite = TextureSprite::with_scale(&texture_sprite.atlas_name, scale);
-                        self.world.texture_sprites.insert(entity, new_sprite);
+                        self.world.insert(entity, new_sprite);
                     }
                 });
 
@@ -842,7 +860,7 @@ impl EditorApp {
                             for sprite_name in &self.asset_manager.available_sprites {
                                 if ui.selectable_value(&mut texture_sprite.atlas_name.clone(), sprite_name.clone(), sprite_name).clicked() {
                                     let new_sprite = TextureSprite::with_scale(sprite_name, scale);
-                                    self.world.texture_sprites.insert(entity, new_sprite);
+                                    self.world.insert(entity, new_sprite);
                                 }
                             }
                         });
@@ -863,22 +881,22 @@ impl EditorApp {
                 ui.label("✅ Physics body active");
                 
                 // Show physics info
-                if let Some(&body_handle) = self.world.entity_to_body.get(&entity) {
-                    if let Some(body) = self.world.physics_world.get(body_handle) {
-                        let pos = body.translation();
-                        let vel = body.linvel();
-                        ui.label(&format!("Physics Pos: ({:.1}, {:.1})", pos.x, pos.y));
-                        ui.label(&format!("Physics Vel: ({:.1}, {:.1})", vel.x, vel.y));
+                if let Some(&body_handle) = self.world.entity_to_body.get(&entity)
+                    && let Some(body) = self.world.physics_world.get(body_handle)
+                {
+                    let pos = body.translation();
+                    let vel = body.linvel();
+                    ui.label(format!("Physics Pos: ({:.1}, {:.1})", pos.x, pos.y));
+                    ui.label(format!("Physics Vel: ({:.1}, {:.1})", vel.x, vel.y));
                         
-                        ui.horizontal(|ui| {
-                            if ui.button("Reset Physics").clicked() {
-                                if let Some(body) = self.world.physics_world.get_mut(body_handle) {
-                                    body.set_linvel(nalgebra::Vector2::zeros(), true);
-                                    body.set_angvel(0.0, true);
-                                }
-                            }
-                        });
-                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Reset Physics").clicked()
+                            && let Some(body) = self.world.physics_world.get_mut(body_handle)
+                        {
+                            body.set_linvel(nalgebra::Vector2::zeros(), true);
+                            body.set_angvel(0.0, true);
+                        }
+                    });
                 }
                 
                 ui.horizontal(|ui| {
@@ -911,9 +929,9 @@ impl EditorApp {
         let available_components = self.editor_state.component_editor.available_components.clone();
         for component_type in available_components {
             let can_add = match component_type {
-                ComponentType::Position => !self.world.positions.contains_key(&entity),
-                ComponentType::Velocity => !self.world.velocities.contains_key(&entity),
-                ComponentType::TextureSprite => !self.world.texture_sprites.contains_key(&entity),
+                ComponentType::Position => !self.world.has::<Position>(entity),
+                ComponentType::Velocity => !self.world.has::<Velocity>(entity),
+                ComponentType::TextureSprite => !self.world.has::<TextureSprite>(entity),
                 ComponentType::PhysicsBody => !self.world.entity_to_body.contains_key(&entity),
             };
 
@@ -963,18 +981,7 @@ impl EditorApp {
     }
 
     fn remove_physics_body(&mut self, entity: Entity) {
-        if let Some(&body_handle) = self.world.entity_to_body.get(&entity) {
-            self.world.physics_world.remove(
-                body_handle,
-                &mut self.world.island_manager,
-                &mut self.world.collider_set,
-                &mut self.world.impulse_joint_set,
-                &mut self.world.multibody_joint_set,
-                true
-            );
-            self.world.entity_to_body.remove(&entity);
-            self.world.body_to_entity.remove(&body_handle);
-        }
+        self.world.remove_physics_body(entity);
     }
 
     fn draw_grid_lines(&self, painter: &egui::Painter, rect: egui::Rect) {
@@ -999,14 +1006,14 @@ impl EditorApp {
     fn draw_grid_entities(&self, painter: &egui::Painter, rect: egui::Rect) {
         // When playing, render based on live world positions
         if self.game_state == GameState::Playing {
-            for (&entity, position) in &self.world.positions {
+            for (entity, position) in self.world.iter::<Position>() {
                 let screen_pos = self.world_to_screen(position.x, position.y, rect);
                 let size = self.grid_editor.cell_size * 0.8;
                 let entity_rect = egui::Rect::from_center_size(screen_pos, egui::vec2(size, size));
 
                 let color = if self.editor_state.selected_entity == Some(entity) {
                     egui::Color32::YELLOW
-                } else if self.world.texture_sprites.contains_key(&entity) {
+                } else if self.world.has::<TextureSprite>(entity) {
                     egui::Color32::from_rgb(100, 150, 255)
                 } else if self.world.entity_to_body.contains_key(&entity) {
                     egui::Color32::from_rgb(255, 150, 100)
@@ -1019,7 +1026,7 @@ impl EditorApp {
                     painter.rect_stroke(entity_rect, 4.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
                 }
 
-                let display_text = if let Some(texture_sprite) = self.world.texture_sprites.get(&entity) {
+                let display_text = if let Some(texture_sprite) = self.world.get::<TextureSprite>(entity) {
                     texture_sprite.atlas_name.chars().take(8).collect::<String>()
                 } else {
                     format!("{}", entity)
@@ -1047,11 +1054,11 @@ impl EditorApp {
 
             let color = if self.editor_state.selected_entity == Some(entity) {
                 egui::Color32::YELLOW
-            } else if self.world.texture_sprites.contains_key(&entity) {
+            } else if self.world.has::<TextureSprite>(entity) {
                 egui::Color32::from_rgb(100, 150, 255)
             } else if self.world.entity_to_body.contains_key(&entity) {
                 egui::Color32::from_rgb(255, 150, 100)
-            } else if self.world.positions.contains_key(&entity) {
+            } else if self.world.has::<Position>(entity) {
                 egui::Color32::from_rgb(150, 255, 150)
             } else {
                 egui::Color32::GRAY
@@ -1062,7 +1069,7 @@ impl EditorApp {
                 painter.rect_stroke(entity_rect, 4.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
             }
 
-            let display_text = if let Some(texture_sprite) = self.world.texture_sprites.get(&entity) {
+            let display_text = if let Some(texture_sprite) = self.world.get::<TextureSprite>(entity) {
                 texture_sprite.atlas_name.chars().take(8).collect::<String>()
             } else {
                 format!("{}", entity)
@@ -1078,15 +1085,15 @@ impl EditorApp {
     }
 
     fn handle_entity_interaction(&mut self, response: &egui::Response, rect: egui::Rect) {
-        if response.clicked() {
-            if let Some(pos) = response.interact_pointer_pos() {
-                let grid_pos = self.screen_to_grid(pos, rect);
+        if response.clicked()
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            let grid_pos = self.screen_to_grid(pos, rect);
                 
-                if let Some(&entity) = self.grid_editor.grid_entities.get(&grid_pos) {
-                    self.editor_state.selected_entity = Some(entity);
-                } else {
-                    self.editor_state.selected_entity = None;
-                }
+            if let Some(&entity) = self.grid_editor.grid_entities.get(&grid_pos) {
+                self.editor_state.selected_entity = Some(entity);
+            } else {
+                self.editor_state.selected_entity = None;
             }
         }
     }
@@ -1127,9 +1134,8 @@ impl EditorApp {
         
         // Remove from hierarchy
         self.hierarchy.root_entities.retain(|&e| e != entity);
-        self.hierarchy.parent_child_map.remove(&entity);
         
-        // Remove from world
+        // Remove from world (children are despawned with it)
         self.world.remove_entity(entity);
     }
 }
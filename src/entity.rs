@@ -0,0 +1,232 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Entity handle made of a slot index and a generation counter.
+///
+/// Slots are recycled when entities are removed, and every reuse bumps the
+/// generation, so a stale handle never resolves to the newer entity that
+/// took over its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    /// A handle that never refers to a live entity, for fields that are
+    /// filled in later
+    pub const PLACEHOLDER: Entity = Entity::from_raw_parts(u32::MAX, u32::MAX);
+
+    /// Create an entity handle from its raw parts
+    pub const fn from_raw_parts(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    /// Slot index of this entity
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Generation of the slot when this handle was created
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Pack the handle into a single u64 (generation in the high bits)
+    pub fn to_bits(&self) -> u64 {
+        ((self.generation as u64) << 32) | self.index as u64
+    }
+
+    /// Rebuild a handle from the value returned by `to_bits`
+    pub fn from_bits(bits: u64) -> Self {
+        Self {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        }
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Per-slot bookkeeping
+#[derive(Debug, Clone, Copy)]
+struct EntityMeta {
+    generation: u32,
+    alive: bool,
+}
+
+/// Hands out entity handles without a `&mut Entities` borrow, so deferred
+/// commands can spawn while the world is busy. Reserved handles become live
+/// on the next `Entities::flush`.
+#[derive(Debug, Default)]
+pub struct EntityReserver {
+    /// Freed slots, already carrying the generation of their next use
+    free_list: Mutex<Vec<Entity>>,
+    /// Next never-used slot index
+    next_index: AtomicU32,
+    /// Handles reserved since the last flush
+    pending: Mutex<Vec<Entity>>,
+}
+
+impl EntityReserver {
+    /// Reserve a handle, reusing a freed slot when one is available
+    pub fn reserve(&self) -> Entity {
+        let entity = self.next_handle();
+        self.pending.lock().unwrap().push(entity);
+        entity
+    }
+
+    /// Take a handle nobody else holds: a freed slot or a never-used one
+    fn next_handle(&self) -> Entity {
+        let recycled = self.free_list.lock().unwrap().pop();
+        recycled.unwrap_or_else(|| Entity::from_raw_parts(self.next_index.fetch_add(1, Ordering::Relaxed), 0))
+    }
+}
+
+/// Entity allocator with a free list of recycled slots
+#[derive(Debug, Default)]
+pub struct Entities {
+    meta: Vec<EntityMeta>,
+    reserver: Arc<EntityReserver>,
+    alive_count: usize,
+}
+
+impl Entities {
+    /// Create an empty allocator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate a new entity, reusing a freed slot when one is available.
+    /// Handles reserved by commands stay pending until `flush`.
+    pub fn alloc(&mut self) -> Entity {
+        let entity = self.reserver.next_handle();
+        self.make_live(entity);
+        entity
+    }
+
+    /// Shared handle for reserving entities from outside the world
+    pub fn reserver(&self) -> Arc<EntityReserver> {
+        Arc::clone(&self.reserver)
+    }
+
+    /// Make every reserved handle live
+    pub fn flush(&mut self) {
+        let pending = std::mem::take(&mut *self.reserver.pending.lock().unwrap());
+        for entity in pending {
+            self.make_live(entity);
+        }
+    }
+
+    fn make_live(&mut self, entity: Entity) {
+        let index = entity.index as usize;
+        if index >= self.meta.len() {
+            self.meta.resize(index + 1, EntityMeta { generation: 0, alive: false });
+        }
+        self.meta[index] = EntityMeta { generation: entity.generation, alive: true };
+        self.alive_count += 1;
+    }
+
+    /// Free an entity. Returns false if the handle was already dead.
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let meta = &mut self.meta[entity.index as usize];
+        meta.alive = false;
+        meta.generation = meta.generation.wrapping_add(1);
+        let recycled = Entity::from_raw_parts(entity.index, meta.generation);
+        self.reserver.free_list.lock().unwrap().push(recycled);
+        self.alive_count -= 1;
+        true
+    }
+
+    /// Check whether a handle still refers to a live entity
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.meta
+            .get(entity.index as usize)
+            .is_some_and(|meta| meta.alive && meta.generation == entity.generation)
+    }
+
+    /// Number of live entities
+    pub fn len(&self) -> usize {
+        self.alive_count
+    }
+
+    /// Whether there are no live entities
+    pub fn is_empty(&self) -> bool {
+        self.alive_count == 0
+    }
+
+    /// Iterate over all live entities in slot order
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.meta
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| meta.alive)
+            .map(|(index, meta)| Entity::from_raw_parts(index as u32, meta.generation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_recycling_bumps_generation() {
+        let mut entities = Entities::new();
+        let first = entities.alloc();
+        let second = entities.alloc();
+        assert_eq!(entities.len(), 2);
+
+        assert!(entities.free(first));
+        assert!(!entities.free(first));
+        assert!(!entities.is_alive(first));
+        assert_eq!(entities.len(), 1);
+
+        let recycled = entities.alloc();
+        assert_eq!(recycled.index(), first.index());
+        assert_ne!(recycled.generation(), first.generation());
+        assert!(entities.is_alive(recycled));
+        assert!(!entities.is_alive(first));
+        assert!(entities.is_alive(second));
+        assert_eq!(entities.iter().collect::<Vec<_>>(), vec![recycled, second]);
+    }
+
+    #[test]
+    fn test_reserved_entities_become_live_on_flush() {
+        let mut entities = Entities::new();
+        let freed = entities.alloc();
+        entities.free(freed);
+
+        let reserver = entities.reserver();
+        let recycled = reserver.reserve();
+        let fresh = reserver.reserve();
+        assert_eq!(recycled.index(), freed.index());
+        assert!(!entities.is_alive(recycled));
+
+        // Allocating directly leaves the reservations pending
+        let direct = entities.alloc();
+        assert!(!entities.is_alive(fresh));
+        assert_eq!(entities.len(), 1);
+        entities.free(direct);
+
+        entities.flush();
+        assert!(entities.is_alive(recycled));
+        assert!(entities.is_alive(fresh));
+        assert_eq!(entities.len(), 2);
+        assert_ne!(entities.alloc(), fresh);
+    }
+
+    #[test]
+    fn test_bits_round_trip() {
+        let entity = Entity::from_raw_parts(42, 7);
+        assert_eq!(Entity::from_bits(entity.to_bits()), entity);
+    }
+}
@@ -0,0 +1,314 @@
+use std::marker::PhantomData;
+use nalgebra::Vector2;
+use crate::entity::Entity;
+use crate::resources::Resource;
+
+/// Marker trait for values sent between systems through the world
+pub trait Event: Send + Sync + 'static {}
+
+/// Double-buffered queue of events of one type, stored as a world resource.
+///
+/// Events sent during a frame stay readable until the end of the next frame,
+/// so every system sees them once regardless of where it runs in the schedule.
+/// `World::update_events` swaps the buffers at the frame boundary.
+pub struct Events<E: Event> {
+    /// Events sent before the last frame boundary
+    previous: Vec<E>,
+    /// Events sent since the last frame boundary
+    current: Vec<E>,
+    /// Id of the first event in `previous`
+    previous_start: usize,
+    /// Total number of events ever sent
+    event_count: usize,
+}
+
+impl<E: Event> Events<E> {
+    /// Create an empty queue
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            previous_start: 0,
+            event_count: 0,
+        }
+    }
+
+    /// Queue an event
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+        self.event_count += 1;
+    }
+
+    /// Drop events older than one frame and start a new buffer
+    pub fn update(&mut self) {
+        self.previous_start += self.previous.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Drop every buffered event
+    pub fn clear(&mut self) {
+        self.previous_start = self.event_count;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Number of events currently buffered
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Whether no events are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over buffered events with an id of at least `since`
+    fn iter_since(&self, since: usize) -> impl Iterator<Item = &E> {
+        let skip = since.saturating_sub(self.previous_start);
+        self.previous.iter().chain(self.current.iter()).skip(skip)
+    }
+}
+
+impl<E: Event> Default for Events<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Event> Resource for Events<E> {}
+
+/// Per-reader cursor into an `Events<E>` queue.
+///
+/// Each system keeps its own reader, so several systems can consume the same
+/// events independently and nobody sees an event twice.
+pub struct EventReader<E: Event> {
+    last_event_count: usize,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<E: Event> EventReader<E> {
+    /// Create a reader that sees every event still buffered
+    pub fn new() -> Self {
+        Self {
+            last_event_count: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Read the events sent since this reader last read
+    pub fn read<'a>(&mut self, events: &'a Events<E>) -> impl Iterator<Item = &'a E> + use<'a, E> {
+        let since = self.last_event_count;
+        self.last_event_count = events.event_count;
+        events.iter_since(since)
+    }
+
+    /// Skip every pending event without reading it
+    pub fn clear(&mut self, events: &Events<E>) {
+        self.last_event_count = events.event_count;
+    }
+}
+
+impl<E: Event> Default for EventReader<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One point where two colliders touch, after the physics step that sent it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    /// World-space point on the first entity's collider
+    pub point: Vector2<f32>,
+    /// World-space direction from the first entity toward the second
+    pub normal: Vector2<f32>,
+    /// Distance between the colliders along `normal`; negative when they overlap
+    pub distance: f32,
+    /// Impulse the solver applied along `normal` in the step; zero in
+    /// `CollisionStarted`, as new contacts are found after solving
+    pub impulse: f32,
+}
+
+/// Two physics bodies started touching
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionStarted {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    pub contacts: Vec<ContactPoint>,
+}
+
+impl Event for CollisionStarted {}
+
+/// Two physics bodies stopped touching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionStopped {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    /// Whether they stopped because one of the bodies was removed
+    pub removed: bool,
+}
+
+impl Event for CollisionStopped {}
+
+/// A body started overlapping a sensor (see `PhysicsBodyOptions::sensor`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorEntered {
+    pub sensor: Entity,
+    pub entity: Entity,
+}
+
+impl Event for SensorEntered {}
+
+/// A body stopped overlapping a sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorExited {
+    pub sensor: Entity,
+    pub entity: Entity,
+    /// Whether it stopped because the sensor or the body was removed
+    pub removed: bool,
+}
+
+impl Event for SensorExited {}
+
+/// Two physics bodies pushed on each other harder than the
+/// `PhysicsBodyOptions::contact_force_threshold` of either during a physics step
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactForce {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    /// Sum of the contact forces on the first entity's collider
+    pub total_force: Vector2<f32>,
+    /// Sum of the magnitudes of the contact forces; not the magnitude of `total_force`
+    pub total_force_magnitude: f32,
+    /// Direction of the strongest contact force
+    pub max_force_direction: Vector2<f32>,
+    pub max_force_magnitude: f32,
+    pub contacts: Vec<ContactPoint>,
+}
+
+impl Event for ContactForce {}
+
+/// An entity was removed from the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityDespawned {
+    pub entity: Entity,
+}
+
+impl Event for EntityDespawned {}
+
+/// A body left the `WorldBounds` with `BoundsBehavior::Despawn` and its
+/// entity is about to be removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutOfBounds {
+    pub entity: Entity,
+    /// Where the body was when it left
+    pub position: Vector2<f32>,
+}
+
+impl Event for OutOfBounds {}
+
+/// A scene finished spawning
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneLoaded {
+    pub name: String,
+    /// Spawned entities in scene order
+    pub entities: Vec<Entity>,
+}
+
+impl Event for SceneLoaded {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scored(u32);
+    impl Event for Scored {}
+
+    #[test]
+    fn test_events_live_for_two_frames() {
+        let mut events = Events::new();
+        let mut reader = EventReader::new();
+        let mut late_reader = EventReader::new();
+
+        events.send(Scored(1));
+        let read: Vec<u32> = reader.read(&events).map(|e| e.0).collect();
+        assert_eq!(read, vec![1]);
+
+        events.update();
+        events.send(Scored(2));
+        // Each reader only sees what it has not read yet
+        assert_eq!(reader.read(&events).map(|e| e.0).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(late_reader.read(&events).map(|e| e.0).collect::<Vec<_>>(), vec![1, 2]);
+
+        events.update();
+        events.update();
+        assert!(events.is_empty());
+        assert_eq!(reader.read(&events).count(), 0);
+    }
+
+    #[test]
+    fn test_physics_steps_send_collision_events() {
+        use crate::components::{PhysicsBodyOptions, PhysicsMaterial, Position};
+        use crate::resources::PhysicsConfig;
+        use crate::world::World;
+        use rapier2d::prelude::RigidBodyType;
+
+        let mut world = World::new();
+        world.insert_resource(PhysicsConfig::with_gravity(500.0));
+        let crate_box = world.spawn(());
+        let no_bounce = PhysicsBodyOptions::new()
+            .with_material(PhysicsMaterial { restitution: 0.0, ..Default::default() });
+        let dynamic = RigidBodyType::Dynamic;
+        let with_forces = no_bounce.with_contact_force_events(0.0);
+        world.add_physics_body_with_options(crate_box, Position::new(100.0, 100.0), 20.0, dynamic, with_forces);
+        let floor = world.spawn(());
+        world.add_physics_body_with_options(floor, Position::new(100.0, 140.0), 20.0, RigidBodyType::Fixed, no_bounce);
+
+        let mut started = EventReader::<CollisionStarted>::new();
+        let mut forces = EventReader::<ContactForce>::new();
+        let mut stopped = EventReader::<CollisionStopped>::new();
+        let mut landed = None;
+        for _ in 0..30 {
+            world.step_physics();
+            landed = landed.or_else(|| world.read_events(&mut started).next().cloned());
+        }
+        let landed = landed.expect("the box lands on the floor");
+        assert_eq!((landed.entity1, landed.entity2), (crate_box, floor));
+        assert!(!landed.contacts.is_empty());
+        for contact in &landed.contacts {
+            assert!((contact.normal.y - 1.0).abs() < 1e-3, "normal points from entity1 to entity2");
+            assert!((contact.point.y - 130.0).abs() < 2.0, "point where the bodies meet");
+        }
+        let force = world.read_events(&mut forces).last().cloned().unwrap();
+        assert_eq!((force.entity1, force.entity2), (crate_box, floor));
+        assert!(force.total_force.y > 0.0 && force.contacts.iter().any(|contact| contact.impulse > 0.0));
+        assert_eq!(world.read_events(&mut stopped).count(), 0, "the box rests on the floor");
+
+        world.remove_entity(floor);
+        world.step_physics();
+        let events: Vec<_> = world.read_events(&mut stopped).copied().collect();
+        assert_eq!(events, vec![CollisionStopped { entity1: crate_box, entity2: floor, removed: true }]);
+    }
+
+    #[test]
+    fn test_contact_forces_are_opt_in() {
+        use crate::components::Position;
+        use crate::resources::PhysicsConfig;
+        use crate::world::World;
+        use rapier2d::prelude::RigidBodyType;
+
+        let mut world = World::new();
+        world.insert_resource(PhysicsConfig::with_gravity(500.0));
+        let resting = world.spawn(());
+        world.add_physics_body(resting, Position::new(100.0, 110.0), 20.0, RigidBodyType::Dynamic);
+        let floor = world.spawn(());
+        world.add_physics_body(floor, Position::new(100.0, 130.0), 20.0, RigidBodyType::Fixed);
+
+        let mut started = EventReader::<CollisionStarted>::new();
+        let mut forces = EventReader::<ContactForce>::new();
+        for _ in 0..60 {
+            world.step_physics();
+        }
+        assert_eq!(world.read_events(&mut started).count(), 1, "the body touches the floor");
+        assert_eq!(world.read_events(&mut forces).count(), 0, "resting contacts send no forces");
+    }
+}
@@ -0,0 +1,271 @@
+use nalgebra::{Point2, Unit, Vector2};
+use rapier2d::prelude::{
+    FixedJointBuilder, GenericJoint, JointAxis, PrismaticJointBuilder, RevoluteJointBuilder, RopeJointBuilder,
+    SpringJointBuilder,
+};
+use serde::{Deserialize, Serialize};
+use crate::entity::Entity;
+
+/// How two physics bodies are held together, for `World::add_joint`.
+///
+/// Anchors are in pixels relative to each body's position, e.g. a door
+/// hinged at its left edge has `anchor_a: (-16.0, 0.0)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointDesc {
+    pub kind: JointKind,
+    /// Where the joint attaches to the first entity
+    #[serde(default)]
+    pub anchor_a: (f32, f32),
+    /// Where the joint attaches to the second entity
+    #[serde(default)]
+    pub anchor_b: (f32, f32),
+    /// Stop the two bodies from colliding with each other, e.g. neighbouring ragdoll limbs
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_contacts: bool,
+}
+
+/// The joint types `JointDesc` can describe
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JointKind {
+    /// Hinge turning about the anchors, e.g. doors, wheels and ragdoll
+    /// limbs; limits are angles in radians
+    Revolute {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limits: Option<(f32, f32)>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        motor: Option<JointMotor>,
+    },
+    /// Slider along `axis` of the first body, e.g. lifts and pistons;
+    /// limits are distances in pixels
+    Prismatic {
+        axis: (f32, f32),
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limits: Option<(f32, f32)>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        motor: Option<JointMotor>,
+    },
+    /// Welds the bodies together
+    Fixed,
+    /// Keeps the anchors at most `length` pixels apart, slack below that
+    Rope { length: f32 },
+    /// Pulls the anchors towards `rest_length` pixels apart
+    Spring { rest_length: f32, stiffness: f32, damping: f32 },
+}
+
+/// Drives a revolute or prismatic joint towards a position and/or velocity
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JointMotor {
+    /// Radians for revolute joints, pixels for prismatic ones
+    pub target_position: f32,
+    pub target_velocity: f32,
+    /// Pull towards `target_position`
+    pub stiffness: f32,
+    /// Pull towards `target_velocity`
+    pub damping: f32,
+    /// Strongest force (or torque) the motor may apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_force: Option<f32>,
+}
+
+impl JointMotor {
+    /// Turn or slide at `speed`; higher `factor` reaches it faster
+    pub fn velocity(speed: f32, factor: f32) -> Self {
+        Self { target_velocity: speed, damping: factor, ..Self::default() }
+    }
+
+    /// Hold at `target` like a spring
+    pub fn position(target: f32, stiffness: f32, damping: f32) -> Self {
+        Self { target_position: target, stiffness, damping, ..Self::default() }
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = Some(max_force);
+        self
+    }
+}
+
+impl JointDesc {
+    /// A joint of `kind` between the two bodies' positions
+    pub fn new(kind: JointKind) -> Self {
+        Self { kind, anchor_a: (0.0, 0.0), anchor_b: (0.0, 0.0), disable_contacts: false }
+    }
+
+    pub fn revolute() -> Self {
+        Self::new(JointKind::Revolute { limits: None, motor: None })
+    }
+
+    /// Slide along `axis` of the first body
+    pub fn prismatic(axis: (f32, f32)) -> Self {
+        Self::new(JointKind::Prismatic { axis, limits: None, motor: None })
+    }
+
+    pub fn fixed() -> Self {
+        Self::new(JointKind::Fixed)
+    }
+
+    pub fn rope(length: f32) -> Self {
+        Self::new(JointKind::Rope { length })
+    }
+
+    pub fn spring(rest_length: f32, stiffness: f32, damping: f32) -> Self {
+        Self::new(JointKind::Spring { rest_length, stiffness, damping })
+    }
+
+    pub fn with_anchors(mut self, anchor_a: (f32, f32), anchor_b: (f32, f32)) -> Self {
+        self.anchor_a = anchor_a;
+        self.anchor_b = anchor_b;
+        self
+    }
+
+    /// Stop the joined bodies from colliding with each other
+    pub fn without_contacts(mut self) -> Self {
+        self.disable_contacts = true;
+        self
+    }
+
+    /// Limit a revolute or prismatic joint; ignored by other kinds
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        if let JointKind::Revolute { limits, .. } | JointKind::Prismatic { limits, .. } = &mut self.kind {
+            *limits = Some((min, max));
+        }
+        self
+    }
+
+    /// Drive a revolute or prismatic joint; ignored by other kinds
+    pub fn with_motor(mut self, motor: JointMotor) -> Self {
+        if let JointKind::Revolute { motor: slot, .. } | JointKind::Prismatic { motor: slot, .. } = &mut self.kind {
+            *slot = Some(motor);
+        }
+        self
+    }
+
+    /// Build the Rapier joint
+    pub fn to_generic_joint(&self) -> GenericJoint {
+        let mut joint: GenericJoint = match self.kind {
+            JointKind::Revolute { .. } => RevoluteJointBuilder::new().into(),
+            JointKind::Prismatic { axis, .. } => {
+                let axis = Unit::try_new(Vector2::new(axis.0, axis.1), f32::EPSILON).unwrap_or(Vector2::x_axis());
+                PrismaticJointBuilder::new(axis).into()
+            }
+            JointKind::Fixed => FixedJointBuilder::new().into(),
+            JointKind::Rope { length } => RopeJointBuilder::new(length).into(),
+            JointKind::Spring { rest_length, stiffness, damping } => {
+                SpringJointBuilder::new(rest_length, stiffness, damping).into()
+            }
+        };
+        joint
+            .set_local_anchor1(Point2::new(self.anchor_a.0, self.anchor_a.1))
+            .set_local_anchor2(Point2::new(self.anchor_b.0, self.anchor_b.1))
+            .set_contacts_enabled(!self.disable_contacts);
+
+        let (limits, motor) = match self.kind {
+            JointKind::Revolute { limits, motor } | JointKind::Prismatic { limits, motor, .. } => (limits, motor),
+            _ => return joint,
+        };
+        let axis = self.motor_axis().expect("revolute and prismatic joints have a motor axis");
+        if let Some((min, max)) = limits {
+            joint.set_limits(axis, [min, max]);
+        }
+        if let Some(motor) = motor {
+            apply_motor(&mut joint, axis, motor);
+        }
+        joint
+    }
+
+    /// The axis a revolute or prismatic joint's motor drives
+    pub(crate) fn motor_axis(&self) -> Option<JointAxis> {
+        match self.kind {
+            JointKind::Revolute { .. } => Some(JointAxis::AngX),
+            JointKind::Prismatic { .. } => Some(JointAxis::LinX),
+            _ => None,
+        }
+    }
+}
+
+/// Set `motor` on `axis` of a Rapier joint
+pub(crate) fn apply_motor(joint: &mut GenericJoint, axis: JointAxis, motor: JointMotor) {
+    joint.set_motor(axis, motor.target_position, motor.target_velocity, motor.stiffness, motor.damping);
+    joint.set_motor_max_force(axis, motor.max_force.unwrap_or(f32::MAX));
+}
+
+/// A joint created by `World::add_joint`, as listed by `World::joints`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityJoint {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    pub desc: JointDesc,
+}
+
+#[cfg(test)]
+mod tests {
+    use rapier2d::prelude::RigidBodyType;
+    use crate::components::{PhysicsBodyOptions, Position};
+    use crate::resources::PhysicsConfig;
+    use crate::scene::{Scene, SceneLoader};
+    use crate::world::World;
+    use super::*;
+
+    #[test]
+    fn test_joints_hold_bodies_and_go_with_their_entities() {
+        let mut world = World::new();
+        world.insert_resource(PhysicsConfig::with_gravity(200.0));
+        let body = |world: &mut World, x: f32, y: f32, body_type| {
+            let entity = world.spawn(());
+            let options = PhysicsBodyOptions::new().with_can_sleep(false);
+            world.add_physics_body_with_options(entity, Position::new(x, y), 10.0, body_type, options);
+            entity
+        };
+        let ceiling = body(&mut world, 200.0, 100.0, RigidBodyType::Fixed);
+        let bob = body(&mut world, 200.0, 140.0, RigidBodyType::Dynamic);
+        let slider = body(&mut world, 300.0, 100.0, RigidBodyType::Dynamic);
+        let wheel = body(&mut world, 400.0, 100.0, RigidBodyType::Dynamic);
+        let rope = world.add_joint(ceiling, bob, JointDesc::rope(50.0)).unwrap();
+        let rail = JointDesc::prismatic((0.0, 1.0)).with_anchors((100.0, 0.0), (0.0, 0.0)).with_limits(0.0, 20.0);
+        world.add_joint(ceiling, slider, rail).unwrap();
+        let axle = JointDesc::revolute().with_anchors((200.0, 0.0), (0.0, 0.0)).with_motor(JointMotor::velocity(2.0, 1e4));
+        let motor = world.add_joint(ceiling, wheel, axle).unwrap();
+        let bodiless = world.spawn(());
+        assert!(world.add_joint(ceiling, bodiless, JointDesc::fixed()).is_none(), "needs two bodies");
+
+        for _ in 0..60 {
+            world.step_physics();
+        }
+        let body = |entity| &world.physics_world[world.entity_to_body[&entity]];
+        assert!((body(bob).translation().y - 150.0).abs() < 1.0, "hangs at the rope's length");
+        assert!((body(slider).translation().y - 120.0).abs() < 1.0, "slides down to its limit");
+        assert_eq!(body(slider).translation().x, 300.0);
+        assert!((body(wheel).angvel() - 2.0).abs() < 0.1);
+        assert!((body(wheel).translation() - nalgebra::Vector2::new(400.0, 100.0)).norm() < 1.0);
+
+        assert!(world.set_joint_motor(motor, JointMotor::velocity(-2.0, 1e4)));
+        assert!(!world.set_joint_motor(rope, JointMotor::velocity(1.0, 1.0)));
+        for _ in 0..30 {
+            world.step_physics();
+        }
+        assert!((world.physics_world[world.entity_to_body[&wheel]].angvel() + 2.0).abs() < 0.1);
+
+        assert_eq!(world.joints_of(ceiling).count(), 3);
+        world.remove_entity(bob);
+        assert!(world.joint(rope).is_none() && world.impulse_joint_set.get(rope).is_none());
+        world.remove_entity(ceiling);
+        assert_eq!((world.joints().count(), world.impulse_joint_set.len()), (0, 0));
+
+        let scene: Scene = ron::from_str(r#"Scene(
+            name: "Door",
+            description: None,
+            entities: [
+                (name: Some("wall"), position: Some((100.0, 100.0)), physics_body: Some((size: 8.0, body_type: Fixed))),
+                (name: Some("door"), position: Some((120.0, 100.0)), physics_body: Some((size: 8.0, body_type: Dynamic))),
+            ],
+            joints: [
+                (entity_a: "wall", entity_b: "door", joint: (kind: Revolute(limits: Some((0.0, 1.5))), anchor_a: (20.0, 0.0))),
+                (entity_a: "wall", entity_b: "window", joint: (kind: Fixed)),
+            ],
+        )"#).unwrap();
+        let names = SceneLoader::spawn_scene(&scene, &mut world);
+        let joints: Vec<_> = world.joints().map(|(_, joint)| *joint).collect();
+        let hinge = JointDesc::revolute().with_limits(0.0, 1.5).with_anchors((20.0, 0.0), (0.0, 0.0));
+        assert_eq!(joints, vec![EntityJoint { entity_a: names["wall"], entity_b: names["door"], desc: hinge }]);
+    }
+}
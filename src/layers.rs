@@ -0,0 +1,220 @@
+use std::fmt;
+use rapier2d::prelude::{Group, InteractionGroups};
+use serde::{Deserialize, Serialize};
+use crate::resources::Resource;
+
+/// Most layers a project can name, one per bit of a Rapier `Group`
+pub const MAX_COLLISION_LAYERS: usize = 32;
+
+/// The named collision layers of a project, each one bit of Rapier's
+/// `InteractionGroups`.
+///
+/// Listed once in the project config (`ProjectConfig::collision_layers`) and
+/// inserted as a resource by `PhysicsPlugin::with_layers`; scenes and code
+/// refer to layers by name and this turns the names into groups.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct CollisionLayers {
+    names: Vec<String>,
+}
+
+impl CollisionLayers {
+    /// Layers in bit order, e.g. `CollisionLayers::new(["player", "enemy"])`
+    pub fn new<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Result<Self, LayerError> {
+        let mut layers = Self::default();
+        for name in names {
+            let name = name.into();
+            if layers.names.contains(&name) {
+                return Err(LayerError::Duplicate(name));
+            }
+            if layers.names.len() == MAX_COLLISION_LAYERS {
+                return Err(LayerError::TooMany);
+            }
+            layers.names.push(name);
+        }
+        Ok(layers)
+    }
+
+    /// Layer names in bit order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The bit of one layer
+    pub fn layer(&self, name: &str) -> Result<Group, LayerError> {
+        self.names
+            .iter()
+            .position(|layer| layer == name)
+            .map(|bit| Group::from_bits_retain(1 << bit))
+            .ok_or_else(|| LayerError::Unknown(name.to_string()))
+    }
+
+    /// The bits of several layers
+    pub fn group<S: AsRef<str>>(&self, names: &[S]) -> Result<Group, LayerError> {
+        names.iter().try_fold(Group::NONE, |group, name| Ok(group | self.layer(name.as_ref())?))
+    }
+
+    /// Groups of a collider in the `member_of` layers that interacts with
+    /// colliders in the `interacts_with` layers, e.g.
+    /// `layers.groups(&["player_bullet"], &["enemy", "wall"])`
+    pub fn groups<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        member_of: &[S],
+        interacts_with: &[T],
+    ) -> Result<InteractionGroups, LayerError> {
+        Ok(InteractionGroups::new(self.group(member_of)?, self.group(interacts_with)?))
+    }
+
+    /// The groups a scene's `LayerMask` names
+    pub fn resolve(&self, mask: &LayerMask) -> Result<InteractionGroups, LayerError> {
+        let group = |names: &Option<Vec<String>>| names.as_deref().map_or(Ok(Group::ALL), |names| self.group(names));
+        Ok(InteractionGroups::new(group(&mask.member_of)?, group(&mask.interacts_with)?))
+    }
+
+    /// Name the layers of `groups`, for saving them to a scene; bits
+    /// without a layer name are dropped
+    pub fn describe(&self, groups: InteractionGroups) -> LayerMask {
+        let names = |group: Group| {
+            (group != Group::ALL).then(|| {
+                self.names
+                    .iter()
+                    .enumerate()
+                    .filter(|&(bit, _)| group.bits() & (1 << bit) != 0)
+                    .map(|(_, name)| name.clone())
+                    .collect()
+            })
+        };
+        LayerMask { member_of: names(groups.memberships), interacts_with: names(groups.filter) }
+    }
+}
+
+impl TryFrom<Vec<String>> for CollisionLayers {
+    type Error = LayerError;
+
+    fn try_from(names: Vec<String>) -> Result<Self, LayerError> {
+        Self::new(names)
+    }
+}
+
+impl From<CollisionLayers> for Vec<String> {
+    fn from(layers: CollisionLayers) -> Self {
+        layers.names
+    }
+}
+
+impl Resource for CollisionLayers {}
+
+/// Collision layers by name, as written in scenes, e.g.
+/// `(member_of: Some(["pickup"]), interacts_with: Some(["player"]))`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerMask {
+    /// Layers the collider is in; `None` puts it in all of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_of: Option<Vec<String>>,
+    /// Layers whose colliders it interacts with; `None` means all of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interacts_with: Option<Vec<String>>,
+}
+
+/// Why layer names can't be turned into groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerError {
+    /// The name isn't one of the project's layers
+    Unknown(String),
+    /// The project lists a layer twice
+    Duplicate(String),
+    /// The project lists more than `MAX_COLLISION_LAYERS` layers
+    TooMany,
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerError::Unknown(name) => write!(f, "unknown collision layer '{}'", name),
+            LayerError::Duplicate(name) => write!(f, "collision layer '{}' is listed twice", name),
+            LayerError::TooMany => write!(f, "at most {} collision layers are supported", MAX_COLLISION_LAYERS),
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+    use rapier2d::prelude::RigidBodyType;
+    use crate::components::{PhysicsBodyOptions, Position, Velocity};
+    use crate::events::{CollisionStarted, EventReader};
+    use crate::scene::PhysicsBodyData;
+    use crate::spatial::SpatialFilter;
+    use crate::world::World;
+    use super::*;
+
+    #[test]
+    fn test_layers_filter_collisions_queries_and_scenes() {
+        let layers = CollisionLayers::new(["player", "player_bullet", "enemy"]).unwrap();
+        assert_eq!(layers.layer("enemy"), Ok(Group::GROUP_3));
+        assert_eq!(layers.layer("pickup"), Err(LayerError::Unknown("pickup".to_string())));
+        assert_eq!(CollisionLayers::new(["a", "a"]), Err(LayerError::Duplicate("a".to_string())));
+        assert_eq!(CollisionLayers::new((0..33).map(|i| i.to_string())), Err(LayerError::TooMany));
+
+        let bullet_groups = layers.groups(&["player_bullet"], &["enemy"]).unwrap();
+        let mask = LayerMask {
+            member_of: Some(vec!["player_bullet".to_string()]),
+            interacts_with: Some(vec!["enemy".to_string()]),
+        };
+        assert_eq!(layers.describe(bullet_groups), mask);
+        assert_eq!(layers.resolve(&mask), Ok(bullet_groups));
+        let data: PhysicsBodyData = ron::from_str(
+            "(size: 4.0, body_type: Dynamic, collision_layers: Some((member_of: Some([\"player_bullet\"]), \
+             interacts_with: Some([\"enemy\"]))))",
+        )
+        .unwrap();
+        assert_eq!(data.body_options(&layers).unwrap().collision_groups, Some(bullet_groups));
+
+        // A bullet fired from inside the player passes through it and hits the enemy
+        let mut world = World::new();
+        world.insert_resource(layers.clone());
+        let player = world.spawn(());
+        let player_groups = layers.groups(&["player"], &["enemy"]).unwrap();
+        let options = PhysicsBodyOptions::new().with_collision_groups(player_groups);
+        world.add_physics_body_with_options(player, Position::new(100.0, 100.0), 32.0, RigidBodyType::Fixed, options);
+        let enemy = world.spawn(());
+        let enemy_groups = layers.groups(&["enemy"], &["player", "player_bullet"]).unwrap();
+        let options = PhysicsBodyOptions::new().with_collision_groups(enemy_groups);
+        world.add_physics_body_with_options(enemy, Position::new(200.0, 100.0), 32.0, RigidBodyType::Fixed, options);
+        let bullet = world.spawn((Position::new(100.0, 100.0), Velocity::new(300.0, 0.0)));
+        let options = PhysicsBodyOptions::new().with_collision_groups(bullet_groups);
+        world.add_physics_body_with_options(bullet, Position::new(100.0, 100.0), 4.0, RigidBodyType::Dynamic, options);
+
+        let mut started = EventReader::<CollisionStarted>::new();
+        let mut hits = Vec::new();
+        for _ in 0..30 {
+            world.step_physics();
+            hits.extend(world.read_events(&mut started).map(|event| [event.entity1, event.entity2]));
+        }
+        assert!(!hits.is_empty());
+        assert!(hits.iter().all(|pair| pair.contains(&bullet) && pair.contains(&enemy)), "{:?}", hits);
+
+        let both = Vector2::new(100.0, 100.0);
+        let mut everything = world.entities_at_point(both, &SpatialFilter::new());
+        everything.retain(|&entity| entity != bullet);
+        assert_eq!(everything, vec![player]);
+        let bullets_view = SpatialFilter::new().with_groups(bullet_groups);
+        assert!(world.entities_at_point(both, &bullets_view).is_empty());
+
+        // Without solver groups in common, the enemy is still hit but not pushed against
+        let ghost = world.spawn((Position::new(100.0, 110.0), Velocity::new(300.0, 0.0)));
+        let options = PhysicsBodyOptions::new()
+            .with_collision_groups(bullet_groups)
+            .with_solver_groups(InteractionGroups::none());
+        world.add_physics_body_with_options(ghost, Position::new(100.0, 110.0), 4.0, RigidBodyType::Dynamic, options);
+        let mut ghost_hit = false;
+        for _ in 0..60 {
+            world.step_physics();
+            ghost_hit |= world.read_events(&mut started).any(|event| [event.entity1, event.entity2].contains(&ghost));
+        }
+        assert!(ghost_hit);
+        assert!(world.get::<Position>(ghost).unwrap().x > 300.0);
+    }
+}
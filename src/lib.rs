@@ -8,6 +8,28 @@
 //! - **Rapier2D Physics**: Professional-grade physics simulation with collision detection
 //! - **Sprite System**: Support for both basic sprites and texture atlases
 //! - **Asset Loading**: PNG loading and sprite atlas management
+//! - **Resources**: Typed global state (time, input, screen size) shared by all systems
+//! - **Events**: Double-buffered typed events for communication between systems
+//! - **Commands**: Deferred spawn/despawn/insert/remove applied between systems
+//! - **Hierarchy**: `Parent`/`Children` links with local `Transform` propagated to `GlobalTransform`
+//! - **Change Detection**: `Added<T>`/`Changed<T>` query filters and `RemovedComponents<T>` readers
+//! - **Staged Scheduling**: Systems run in stages, ordered by `before`/`after` labels
+//! - **State Machines**: `States<S>` resources with queued transitions and `on_enter`/`on_exit`/`on_update` systems
+//! - **Run Conditions**: `run_if` closures over the world; systems toggled or removed by name at runtime
+//! - **Parallel Systems**: Systems declaring their reads/writes run concurrently on a thread pool
+//! - **Collision Events**: `CollisionStarted`/`CollisionStopped`/`ContactForce` events with contact points and impulses
+//! - **Sensors**: Trigger colliders sending `SensorEntered`/`SensorExited`, plus point and shape overlap queries
+//! - **Spatial Queries**: `cast_ray`/`cast_shape`/`project_point` returning entities, filtered by `SpatialFilter`
+//! - **Collision Layers**: Named layers from `project.ron` mapped to Rapier collision and solver groups, also in scenes
+//! - **Character Controller**: `CharacterController` moving kinematic bodies with slopes, steps, one-way platforms and ground flags
+//! - **Joints**: Revolute, prismatic, fixed, rope and spring joints between entities with limits and motors, also in scenes
+//! - **Collider Shapes**: `ColliderShape` balls, boxes, capsules, polygons, meshes and compounds, fitted to sprites
+//! - **World Bounds**: `WorldBounds` wrapping, clamping, bouncing or despawning bodies at the level's edges, or walling them in
+//! - **Physics Settings**: `PhysicsConfig` resource and per-body `PhysicsBodyOptions`, also in scene files
+//! - **Fixed Timestep**: `FixedUpdate` runs in fixed steps; physics poses are interpolated for rendering
+//! - **Profiling**: Rolling per-system min/avg/max/p99 timings, exported as CSV, JSON or Chrome traces
+//! - **Names and Tags**: `Name`/`Tags` components indexed for `find_by_name` and `iter_tagged`
+//! - **App and Plugins**: `App` builder assembling the world and schedule from `Physics`/`Render`/`Input`/`Scene` plugins
 //! - **Modular Design**: Separated components, systems, and world management
 //! 
 //! ## Basic Usage
@@ -23,27 +45,72 @@
 //! world.add_position(entity, Position::new(100.0, 100.0));
 //! world.add_velocity(entity, Velocity::new(50.0, 0.0));
 //! 
-//! // Set up systems
+//! // Game-specific components only need to implement `Component`
+//! struct Health(u32);
+//! impl Component for Health {}
+//! world.insert(entity, Health(100));
+//! assert_eq!(world.get::<Health>(entity).map(|h| h.0), Some(100));
+//! 
+//! // Global state lives in resources
+//! struct Score(u32);
+//! impl Resource for Score {}
+//! world.insert_resource(Score(0));
+//! if let Some(score) = world.resource_mut::<Score>() {
+//!     score.0 += 10;
+//! }
+//! 
+//! // Set up systems; stages and before/after labels decide the order
 //! let mut scheduler = Scheduler::new();
-//! scheduler.add_system(Box::new(PhysicsSystem::new()));
+//! scheduler.add_system(Box::new(PhysicsSystem::new())).in_stage(Stage::FixedUpdate);
+//! scheduler.add_system(Box::new(TransformSystem::new())).in_stage(Stage::PostUpdate);
+//! assert_eq!(scheduler.system_names(), vec!["PhysicsSystem", "TransformSystem"]);
 //! 
 //! // Game loop
 //! // scheduler.update(&mut world, dt);
 //! ```
 
 // Re-export all public modules
+pub mod entity;
+pub mod storage;
+pub mod change_detection;
+pub mod resources;
+pub mod events;
+pub mod state;
+pub mod commands;
 pub mod components;
 pub mod systems;
 pub mod world;
+pub mod spatial;
+pub mod joints;
+pub mod layers;
+pub mod project;
 pub mod scene;
 pub mod editor;
+pub mod app;
+pub mod plugins;
 
 // Re-export commonly used types for convenience
 pub use components::*;
 pub use systems::*;
-pub use world::World;
+pub use entity::{Entities, EntityReserver};
+pub use storage::{Bundle, Component, ComponentStorage, Components};
+pub use change_detection::{ComponentTicks, Mut, RemovedComponents, Tick};
+pub use commands::{Commands, EntityCommands};
+pub use resources::{BoundsBehavior, FixedTime, PhysicsConfig, Resource, Resources, ScreenSize, Time, WorldBounds};
+pub use state::{State, StateScoped, States};
+pub use events::{
+    CollisionStarted, CollisionStopped, ContactForce, ContactPoint, EntityDespawned, Event, EventReader, Events, OutOfBounds,
+    SceneLoaded, SensorEntered, SensorExited,
+};
+pub use spatial::{PointProjection, RayHit, SpatialFilter};
+pub use joints::{EntityJoint, JointDesc, JointKind, JointMotor};
+pub use layers::{CollisionLayers, LayerError, LayerMask, MAX_COLLISION_LAYERS};
+pub use project::ProjectConfig;
+pub use world::{ComponentHooks, World};
 pub use scene::*;
 pub use editor::*;
+pub use app::{App, Plugin};
+pub use plugins::{InputPlugin, PhysicsPlugin, RenderPlugin, ScenePlugin};
 
 // Constants
 pub const DEFAULT_WIDTH: usize = 800;
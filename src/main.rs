@@ -1,167 +1,171 @@
-use minifb::{Key, Window, WindowOptions};
-use std::time::Instant;
-
-// Rapier2D imports
-use nalgebra::Vector2;
+use minifb::Key;
 
 // Use RocketEngine as a library
 use rocket_engine::*;
 
-const WIDTH: usize = 800;
-const HEIGHT: usize = 600;
+/// Screens of the game; the simulation only runs while `Playing`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppState {
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+impl State for AppState {}
+
+/// Moves between `AppState`s: Enter starts, P pauses and resumes, and the
+/// game is over once the player is gone
+struct AppStateSystem;
+
+impl System for AppStateSystem {
+    fn update(&mut self, world: &mut World, _dt: f32) {
+        let player_alive = world.find_by_name("player").is_some();
+        let Some(input) = world.resource::<InputState>() else {
+            return;
+        };
+        let (start, pause) = (input.just_pressed(Key::Enter), input.just_pressed(Key::P));
+        let Some(states) = world.resource_mut::<States<AppState>>() else {
+            return;
+        };
+        match states.current() {
+            AppState::MainMenu if start => states.set(AppState::Playing),
+            AppState::Playing if !player_alive => states.set(AppState::GameOver),
+            AppState::Playing if pause => states.set(AppState::Paused),
+            AppState::Paused if pause => states.set(AppState::Playing),
+            _ => {}
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "AppStateSystem"
+    }
+}
+
+/// Prints a message, e.g. when entering a state
+struct Banner(&'static str, &'static str);
+
+impl System for Banner {
+    fn update(&mut self, _world: &mut World, _dt: f32) {
+        println!("{}", self.1);
+    }
+
+    fn name(&self) -> &'static str {
+        self.0
+    }
+}
 
 /// Create a default player entity
 fn create_default_player(world: &mut World) -> Entity {
     let player = world.create_entity();
+    world.set_name(player, "player");
     world.add_position(player, Position::new(100.0, 100.0));
     world.add_velocity(player, Velocity::zero());
     world.add_texture_sprite(player, TextureSprite::with_scale("player", 2.0));
-    world.add_physics_body(player, Position::new(100.0, 100.0), 32.0, rapier2d::prelude::RigidBodyType::Dynamic);
+    let body_type = rapier2d::prelude::RigidBodyType::KinematicPositionBased;
+    world.add_physics_body(player, Position::new(100.0, 100.0), 32.0, body_type);
+    // Moved by InputSystem; top-down, so nothing to snap down onto
+    world.insert(player, CharacterController::new().with_snap_to_ground(None));
     player
 }
 
 /// Create default entities when no scene file is available
-fn create_default_entities(world: &mut World) -> Entity {
+fn create_default_entities(world: &mut World) {
     // Create the player entity with texture sprite and physics body
-    let player = create_default_player(world);
+    create_default_player(world);
 
     // Create additional entities with physics bodies
     let enemy1 = world.create_entity();
+    world.set_name(enemy1, "enemy1");
+    world.add_tag(enemy1, "enemy");
     world.add_position(enemy1, Position::new(300.0, 200.0));
     world.add_velocity(enemy1, Velocity::new(20.0, 15.0));
     world.add_texture_sprite(enemy1, TextureSprite::with_scale("enemy1", 1.5));
     world.add_physics_body(enemy1, Position::new(300.0, 200.0), 24.0, rapier2d::prelude::RigidBodyType::Dynamic);
 
     let enemy2 = world.create_entity();
+    world.set_name(enemy2, "enemy2");
+    world.add_tag(enemy2, "enemy");
     world.add_position(enemy2, Position::new(500.0, 400.0));
     world.add_velocity(enemy2, Velocity::new(-30.0, -20.0));
     world.add_texture_sprite(enemy2, TextureSprite::with_scale("enemy2", 1.0));
     world.add_physics_body(enemy2, Position::new(500.0, 400.0), 16.0, rapier2d::prelude::RigidBodyType::Dynamic);
 
     let powerup = world.create_entity();
+    world.set_name(powerup, "powerup");
     world.add_position(powerup, Position::new(400.0, 300.0));
     world.add_velocity(powerup, Velocity::new(10.0, -10.0));
     world.add_texture_sprite(powerup, TextureSprite::with_name("powerup"));
     world.add_physics_body(powerup, Position::new(400.0, 300.0), 16.0, rapier2d::prelude::RigidBodyType::Dynamic);
-
-    player
 }
 
 fn main() {
-    let mut window = Window::new(
-        "RocketEngine - Powered by Rapier2D",
-        WIDTH,
-        HEIGHT,
-        WindowOptions::default(),
-    )
-    .unwrap_or_else(|e| {
-        panic!("{}", e);
+    let project = ProjectConfig::load("project.ron").unwrap_or_else(|e| {
+        println!("Could not load project.ron: {}", e);
+        ProjectConfig::default()
     });
+    let physics = PhysicsPlugin::default()
+        .with_layers(project.collision_layers)
+        .run_if(in_state(AppState::Playing));
+
+    let mut app = App::new();
+    app.add_plugin(RenderPlugin::default())
+        .add_plugin(InputPlugin::default().run_if(in_state(AppState::Playing)))
+        .add_plugin(physics)
+        // Try loading RON scene first, then JSON scene, then fall back to defaults
+        .add_plugin(
+            ScenePlugin::new("scenes/example_scene.ron")
+                .or_load("scenes/example_scene.json")
+                .with_fallback(create_default_entities),
+        )
+        .add_state(AppState::MainMenu);
+
+    if app.world.find_by_name("player").is_none() {
+        println!("Warning: No player entity found in scene, creating default player");
+        create_default_player(&mut app.world);
+    }
 
-    // Limit to max ~60 fps update rate
-    window.set_target_fps(60);
-
-    let mut buffer: Vec<u32> = vec![0; WIDTH * HEIGHT];
-    let mut world = World::new();
-
-    // Load or create sprite atlas
-    let atlas = match AssetsLoader::load_png("assets/sprites/atlas.png") {
-        Ok(texture) => {
-            println!("Successfully loaded PNG atlas!");
-            let mut atlas = SpriteAtlas::new(texture);
-            atlas.add_sprite("player".to_string(), 0, 0, 32, 32);
-            atlas.add_sprite("enemy1".to_string(), 32, 0, 32, 32);
-            atlas.add_sprite("enemy2".to_string(), 64, 0, 32, 32);
-            atlas.add_sprite("powerup".to_string(), 96, 0, 32, 32);
-            atlas
+    // Game screens; the simulation pauses outside AppState::Playing
+    app.add_system(Box::new(AppStateSystem)).in_stage(Stage::PreUpdate).before("InputSystem");
+    app.add_system(Box::new(Banner("MenuBanner", "Press Enter to start."))).on_enter(AppState::MainMenu);
+    app.add_system(Box::new(Banner("PauseBanner", "Paused. Press P to resume."))).on_enter(AppState::Paused);
+    app.add_system(Box::new(Banner("GameOverBanner", "Game over!"))).on_enter(AppState::GameOver);
+    app.add_query_system(QueryDemoSystem::new()).after("MovementSystem").run_if(on_timer(0.5));
+
+    match app.scheduler.resolved_order() {
+        Ok(order) => println!("{}", order),
+        Err(error) => {
+            eprintln!("Invalid system schedule: {}", error);
+            return;
         }
-        Err(_) => {
-            println!("Could not load PNG atlas, using sample sprites");
-            AssetsLoader::create_sample_atlas()
-        }
-    };
-    
-    world.set_sprite_atlas(atlas);
+    }
 
-    // Load entities from scene file, fall back to hardcoded entities if scene file doesn't exist
-    let player = {
-        // Try loading RON scene first, then JSON scene, then fall back to defaults
-        let scene_result = SceneLoader::load_from_ron("scenes/example_scene.ron")
-            .or_else(|_| SceneLoader::load_from_json("scenes/example_scene.json"));
-        
-        match scene_result {
-            Ok(scene) => {
-                println!("Loading scene from file...");
-                let entity_map = SceneLoader::spawn_scene(&scene, &mut world);
-                // Get the player entity ID from the scene
-                entity_map.get("player").copied().unwrap_or_else(|| {
-                    println!("Warning: No player entity found in scene, creating default player");
-                    create_default_player(&mut world)
-                })
-            }
-            Err(e) => {
-                println!("Could not load scene file: {}", e);
-                println!("Creating default entities...");
-                create_default_entities(&mut world)
-            }
-        }
-    };
-
-    // Setup scheduler with update systems
-    let mut scheduler = Scheduler::new();
-    let mut input_system = InputSystem::new(player);
-    
-    // Add traditional systems
-    scheduler.add_system(Box::new(PhysicsSystem::new()));
-    
-    // Add query-based systems for demonstration
-    scheduler.add_query_system(MovementSystem::new());
-    scheduler.add_query_system(QueryDemoSystem::new());
-
-    // Timing for delta time calculation
-    let mut last_time = Instant::now();
+    // ROCKET_PROFILE=<prefix> times every system and writes <prefix>.csv,
+    // <prefix>.json and <prefix>_trace.json on exit
+    let profile_prefix = std::env::var("ROCKET_PROFILE").ok();
+    if profile_prefix.is_some() {
+        app.scheduler.enable_profiling(600);
+    }
 
     println!("RocketEngine started!");
-    println!("Use arrow keys to move the player sprite.");
+    println!("Use arrow keys to move the player sprite, P to pause.");
     println!("Watch entities interact with realistic physics!");
     println!("Powered by Rapier2D for professional collision detection!");
 
-    while window.is_open() && !window.is_key_down(Key::Escape) {
-        let current_time = Instant::now();
-        let dt = current_time.duration_since(last_time).as_secs_f32();
-        last_time = current_time;
-
-        // Handle input for the player (apply forces to physics body)
-        let mut velocity = Vector2::zeros();
-        let speed = 150.0;
-        
-        if window.is_key_down(Key::Left) {
-            velocity.x = -speed;
-        }
-        if window.is_key_down(Key::Right) {
-            velocity.x = speed;
-        }
-        if window.is_key_down(Key::Up) {
-            velocity.y = -speed;
-        }
-        if window.is_key_down(Key::Down) {
-            velocity.y = speed;
-        }
-        
-        world.set_physics_velocity(player, velocity);
-
-        // Update all systems via scheduler
-        input_system.update(&mut world, dt);
-        scheduler.update(&mut world, dt);
-
-        // Render
-        RenderSystem::render_frame(&mut buffer, &world);
+    if let Err(error) = app.run() {
+        eprintln!("Invalid system schedule: {}", error);
+        return;
+    }
 
-        // Update the window with the buffer
-        window
-            .update_with_buffer(&buffer, WIDTH, HEIGHT)
-            .unwrap();
+    if let (Some(prefix), Some(profiler)) = (profile_prefix, app.scheduler.profiler()) {
+        print!("{}", profiler.to_csv());
+        let saved = profiler.save_csv(format!("{}.csv", prefix))
+            .and_then(|_| profiler.save_json(format!("{}.json", prefix)))
+            .and_then(|_| profiler.save_chrome_trace(format!("{}_trace.json", prefix)));
+        if let Err(e) = saved {
+            eprintln!("Failed to save profile: {}", e);
+        }
     }
 
     println!("Game ended. Thanks for playing!");
-}
\ No newline at end of file
+}
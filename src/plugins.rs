@@ -0,0 +1,298 @@
+use std::rc::Rc;
+use std::time::Instant;
+use minifb::{Key, Window, WindowOptions};
+use crate::app::{App, Plugin};
+use crate::components::{AssetsLoader, SpriteAtlas};
+use crate::layers::CollisionLayers;
+use crate::resources::{PhysicsConfig, ScreenSize, WorldBounds};
+use crate::scene::SceneLoader;
+use crate::systems::{InputState, InputSystem, MovementSystem, PhysicsSystem, RenderSystem, Stage, SystemConfig, VelocitySyncSystem};
+use crate::world::World;
+
+/// Run condition shared by every system a plugin adds
+type SharedCondition = Rc<dyn Fn(&World) -> bool>;
+
+/// Apply a plugin's run condition, if it has one, to a system it adds
+fn gate(config: SystemConfig<'_>, condition: &Option<SharedCondition>) {
+    if let Some(condition) = condition {
+        let condition = Rc::clone(condition);
+        config.run_if(move |world: &World| condition(world));
+    }
+}
+
+/// Rapier simulation: `VelocitySyncSystem`, `PhysicsSystem` and
+/// `MovementSystem` in `Stage::FixedUpdate`, all labelled "physics"
+#[derive(Default)]
+pub struct PhysicsPlugin {
+    config: Option<PhysicsConfig>,
+    layers: Option<CollisionLayers>,
+    /// `Some(None)` removes the default bounds
+    bounds: Option<Option<WorldBounds>>,
+    condition: Option<SharedCondition>,
+}
+
+impl PhysicsPlugin {
+    /// Use `config` instead of `PhysicsConfig::default()`
+    pub fn with_config(mut self, config: PhysicsConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Name collision layers for scenes and `CollisionLayers::groups`,
+    /// usually `ProjectConfig::collision_layers`
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Keep bodies inside `bounds` instead of `WorldBounds::default()`,
+    /// e.g. the size of the level
+    pub fn with_bounds(mut self, bounds: WorldBounds) -> Self {
+        self.bounds = Some(Some(bounds));
+        self
+    }
+
+    /// Let bodies go anywhere
+    pub fn without_bounds(mut self) -> Self {
+        self.bounds = Some(None);
+        self
+    }
+
+    /// Only simulate while `condition` holds, e.g. `in_state(AppState::Playing)`
+    pub fn run_if(mut self, condition: impl Fn(&World) -> bool + 'static) -> Self {
+        self.condition = Some(Rc::new(condition));
+        self
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(config) = self.config {
+            app.insert_resource(config);
+        }
+        if let Some(layers) = &self.layers {
+            app.insert_resource(layers.clone());
+        }
+        match self.bounds {
+            Some(Some(bounds)) => {
+                app.insert_resource(bounds);
+            }
+            Some(None) => {
+                app.world.remove_resource::<WorldBounds>();
+            }
+            None => {}
+        }
+        let sync = app.add_system(Box::new(VelocitySyncSystem::new()))
+            .in_stage(Stage::FixedUpdate)
+            .before("PhysicsSystem")
+            .label("physics");
+        gate(sync, &self.condition);
+        let physics = app.add_system(Box::new(PhysicsSystem::new()))
+            .in_stage(Stage::FixedUpdate)
+            .label("physics");
+        gate(physics, &self.condition);
+        let movement = app.add_query_system(MovementSystem::new())
+            .in_stage(Stage::FixedUpdate)
+            .label("physics");
+        gate(movement, &self.condition);
+    }
+}
+
+/// Keyboard input: the `InputState` resource, plus an `InputSystem` in
+/// `Stage::PreUpdate` moving the entity named "player" by default
+pub struct InputPlugin {
+    player: Option<&'static str>,
+    condition: Option<SharedCondition>,
+}
+
+impl InputPlugin {
+    /// Move the entity named `name` with the arrow keys
+    pub fn with_player(mut self, name: &'static str) -> Self {
+        self.player = Some(name);
+        self
+    }
+
+    /// Only capture keys into `InputState`, without moving anything
+    pub fn without_player(mut self) -> Self {
+        self.player = None;
+        self
+    }
+
+    /// Only move the player while `condition` holds
+    pub fn run_if(mut self, condition: impl Fn(&World) -> bool + 'static) -> Self {
+        self.condition = Some(Rc::new(condition));
+        self
+    }
+}
+
+impl Default for InputPlugin {
+    fn default() -> Self {
+        Self { player: Some("player"), condition: None }
+    }
+}
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.world.init_resource::<InputState>();
+        if let Some(player) = self.player {
+            let input = app.add_system(Box::new(InputSystem::for_player_named(player))).in_stage(Stage::PreUpdate);
+            gate(input, &self.condition);
+        }
+    }
+}
+
+/// Sprite rendering: loads the sprite atlas and, unless headless, opens a
+/// window whose loop captures input, updates the app and draws each frame
+/// with `RenderSystem`
+pub struct RenderPlugin {
+    atlas_path: &'static str,
+    title: &'static str,
+    target_fps: usize,
+    window: bool,
+}
+
+impl RenderPlugin {
+    /// Load the atlas from `path` instead of `assets/sprites/atlas.png`
+    pub fn with_atlas(mut self, path: &'static str) -> Self {
+        self.atlas_path = path;
+        self
+    }
+
+    /// Window title
+    pub fn with_title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Frame rate the window loop is capped at
+    pub fn with_target_fps(mut self, fps: usize) -> Self {
+        self.target_fps = fps;
+        self
+    }
+
+    /// Only load the atlas; the host draws the world itself (e.g. the editor)
+    pub fn headless(mut self) -> Self {
+        self.window = false;
+        self
+    }
+}
+
+impl Default for RenderPlugin {
+    fn default() -> Self {
+        Self {
+            atlas_path: "assets/sprites/atlas.png",
+            title: "RocketEngine - Powered by Rapier2D",
+            target_fps: 60,
+            window: true,
+        }
+    }
+}
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.world.init_resource::<ScreenSize>();
+        app.world.set_sprite_atlas(load_atlas(self.atlas_path));
+        if self.window {
+            let (title, target_fps) = (self.title, self.target_fps);
+            app.set_runner(move |app| run_window(app, title, target_fps));
+        }
+    }
+}
+
+/// Load the sprite atlas, falling back to generated sample sprites
+fn load_atlas(path: &str) -> SpriteAtlas {
+    match AssetsLoader::load_png(path) {
+        Ok(texture) => {
+            println!("Successfully loaded PNG atlas!");
+            let mut atlas = SpriteAtlas::new(texture);
+            atlas.add_sprite("player".to_string(), 0, 0, 32, 32);
+            atlas.add_sprite("enemy1".to_string(), 32, 0, 32, 32);
+            atlas.add_sprite("enemy2".to_string(), 64, 0, 32, 32);
+            atlas.add_sprite("powerup".to_string(), 96, 0, 32, 32);
+            atlas
+        }
+        Err(_) => {
+            println!("Could not load PNG atlas, using sample sprites");
+            AssetsLoader::create_sample_atlas()
+        }
+    }
+}
+
+/// The windowed main loop, until the window closes or Escape is pressed
+fn run_window(app: &mut App, title: &str, target_fps: usize) {
+    let screen = app.world.resource::<ScreenSize>().copied().unwrap_or_default();
+    let mut window = Window::new(title, screen.width, screen.height, WindowOptions::default())
+        .unwrap_or_else(|e| {
+            panic!("{}", e);
+        });
+    window.set_target_fps(target_fps);
+
+    let mut buffer: Vec<u32> = vec![0; screen.pixel_count()];
+    let mut last_time = Instant::now();
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let current_time = Instant::now();
+        let dt = current_time.duration_since(last_time).as_secs_f32();
+        last_time = current_time;
+
+        // Capture keyboard state for InputSystem
+        if let Some(input) = app.world.resource_mut::<InputState>() {
+            input.update_from_window(&window);
+        }
+
+        app.update(dt);
+
+        let render_start = Instant::now();
+        RenderSystem::render_frame(&mut buffer, &app.world);
+        if let Some(profiler) = app.scheduler.profiler_mut() {
+            profiler.record("Render", render_start);
+        }
+
+        window
+            .update_with_buffer(&buffer, screen.width, screen.height)
+            .unwrap();
+    }
+}
+
+/// Spawns the first scene file that loads, or calls a fallback when none does
+pub struct ScenePlugin {
+    paths: Vec<&'static str>,
+    fallback: Option<fn(&mut World)>,
+}
+
+impl ScenePlugin {
+    /// Load `path` (RON for `.ron`, JSON otherwise)
+    pub fn new(path: &'static str) -> Self {
+        Self { paths: vec![path], fallback: None }
+    }
+
+    /// Try `path` if the previous files fail to load
+    pub fn or_load(mut self, path: &'static str) -> Self {
+        self.paths.push(path);
+        self
+    }
+
+    /// Populate the world with `fallback` if no file loads
+    pub fn with_fallback(mut self, fallback: fn(&mut World)) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+}
+
+impl Plugin for ScenePlugin {
+    fn build(&self, app: &mut App) {
+        for path in &self.paths {
+            match SceneLoader::load(path) {
+                Ok(scene) => {
+                    println!("Loading scene from {}...", path);
+                    SceneLoader::spawn_scene(&scene, &mut app.world);
+                    return;
+                }
+                Err(e) => println!("Could not load scene file {}: {}", path, e),
+            }
+        }
+        if let Some(fallback) = self.fallback {
+            println!("Creating default entities...");
+            fallback(&mut app.world);
+        }
+    }
+}
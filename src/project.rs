@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use crate::layers::CollisionLayers;
+
+/// Settings shared by every scene of a game, e.g. `project.ron`:
+///
+/// ```ron
+/// ProjectConfig(
+///     collision_layers: ["player", "player_bullet", "enemy", "pickup", "wall"],
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// Named collision layers, at most 32, in bit order
+    #[serde(default)]
+    pub collision_layers: CollisionLayers,
+}
+
+impl ProjectConfig {
+    /// Load the config from a `.ron` file, or from JSON for any other extension
+    pub fn load(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(file_path)?;
+        if file_path.ends_with(".ron") {
+            Ok(ron::from_str(&contents)?)
+        } else {
+            Ok(serde_json::from_str(&contents)?)
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use nalgebra::Vector2;
+use crate::components::PhysicsMaterial;
+use crate::{DEFAULT_HEIGHT, DEFAULT_WIDTH};
+
+/// Marker trait for global, non-entity state stored in the world
+/// (frame time, input, screen size, score, RNG, ...).
+///
+/// Only one value of each resource type exists per world.
+pub trait Resource: Send + Sync + 'static {}
+
+/// Type-keyed container of resources
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Resources {
+    /// Create an empty container
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a resource, returning the previous value of the same type
+    pub fn insert<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.values
+            .insert(TypeId::of::<R>(), Box::new(resource))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    /// Get a resource
+    pub fn get<R: Resource>(&self) -> Option<&R> {
+        self.values.get(&TypeId::of::<R>())?.downcast_ref()
+    }
+
+    /// Get a resource mutably
+    pub fn get_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.values.get_mut(&TypeId::of::<R>())?.downcast_mut()
+    }
+
+    /// Get a resource mutably, inserting the value returned by `f` if missing
+    pub fn get_or_insert_with<R: Resource>(&mut self, f: impl FnOnce() -> R) -> &mut R {
+        self.values
+            .entry(TypeId::of::<R>())
+            .or_insert_with(|| Box::new(f()))
+            .downcast_mut()
+            .expect("resource stored under the wrong type")
+    }
+
+    /// Remove a resource, returning it
+    pub fn remove<R: Resource>(&mut self) -> Option<R> {
+        self.values
+            .remove(&TypeId::of::<R>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    /// Check whether a resource exists
+    pub fn contains<R: Resource>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Move a resource out, type-erased
+    pub(crate) fn take_raw(&mut self, type_id: TypeId) -> Option<Box<dyn Any + Send + Sync>> {
+        self.values.remove(&type_id)
+    }
+
+    /// Move every resource of `other` back in
+    pub(crate) fn restore(&mut self, other: Resources) {
+        self.values.extend(other.values);
+    }
+
+    /// Insert a type-erased resource
+    pub(crate) fn insert_raw(&mut self, type_id: TypeId, value: Box<dyn Any + Send + Sync>) {
+        self.values.insert(type_id, value);
+    }
+
+    /// Number of stored resources
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no resource has been inserted
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Frame timing, advanced by the `Scheduler` before systems run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Time {
+    /// Seconds since the previous update
+    pub delta: f32,
+    /// Seconds since the first update
+    pub elapsed: f32,
+    /// Number of updates so far
+    pub frame_count: u64,
+}
+
+impl Time {
+    /// Advance the clock by one frame
+    pub fn advance(&mut self, dt: f32) {
+        self.delta = dt;
+        self.elapsed += dt;
+        self.frame_count += 1;
+    }
+}
+
+impl Resource for Time {}
+
+/// Clock for the `FixedUpdate` stage.
+///
+/// Frame time is banked in an accumulator and spent in whole `timestep`
+/// steps, so fixed-stage systems see the same `dt` at any frame rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTime {
+    /// Seconds simulated per fixed step
+    pub timestep: f32,
+    /// Most steps run in one frame; time beyond that is dropped so a slow
+    /// frame cannot snowball into ever longer catch-ups
+    pub max_steps_per_frame: u32,
+    /// Banked time not yet simulated
+    accumulator: f32,
+    /// Steps taken in the latest frame
+    steps_this_frame: u32,
+}
+
+impl FixedTime {
+    /// Create a clock stepping every `timestep` seconds
+    pub fn new(timestep: f32) -> Self {
+        Self {
+            timestep,
+            max_steps_per_frame: 5,
+            accumulator: 0.0,
+            steps_this_frame: 0,
+        }
+    }
+
+    /// Bank a frame's time and return how many fixed steps to run
+    pub fn accumulate(&mut self, dt: f32) -> u32 {
+        // Absorb rounding so e.g. 1/30s frames always yield exactly two 1/60s steps
+        let tolerance = self.timestep * 1e-3;
+        self.accumulator += dt;
+        self.steps_this_frame = 0;
+        while self.accumulator + tolerance >= self.timestep && self.steps_this_frame < self.max_steps_per_frame {
+            self.accumulator -= self.timestep;
+            self.steps_this_frame += 1;
+        }
+        if self.accumulator >= self.timestep {
+            self.accumulator %= self.timestep;
+        }
+        self.steps_this_frame
+    }
+
+    /// How far the frame is between the last two fixed steps, in `0.0..=1.0`,
+    /// for interpolating what they produced
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.timestep).clamp(0.0, 1.0)
+    }
+
+    /// Steps taken in the latest frame
+    pub fn steps_this_frame(&self) -> u32 {
+        self.steps_this_frame
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(1.0 / 60.0)
+    }
+}
+
+impl Resource for FixedTime {}
+
+/// World-wide physics settings, read by every physics step and used as the
+/// defaults of bodies created by `World::add_physics_body`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsConfig {
+    /// Acceleration applied to dynamic bodies, in pixels/s² (y points down)
+    pub gravity: Vector2<f32>,
+    /// Constraint solver iterations per step; more is stiffer but slower
+    pub solver_iterations: usize,
+    /// Whether new bodies use continuous collision detection
+    pub ccd_enabled: bool,
+    /// Most CCD substeps per physics step
+    pub max_ccd_substeps: usize,
+    /// Material of new colliders
+    pub material: PhysicsMaterial,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    /// Whether resting bodies may stop being simulated until something touches them
+    pub can_sleep: bool,
+}
+
+impl PhysicsConfig {
+    /// Default settings with downward gravity, e.g. `PhysicsConfig::with_gravity(980.0)`
+    pub fn with_gravity(gravity: f32) -> Self {
+        Self { gravity: Vector2::new(0.0, gravity), ..Self::default() }
+    }
+}
+
+impl Default for PhysicsConfig {
+    /// Top-down, "space-like" physics: no gravity, bouncy bodies that never sleep
+    fn default() -> Self {
+        Self {
+            gravity: Vector2::zeros(),
+            solver_iterations: 4,
+            ccd_enabled: true,
+            max_ccd_substeps: 4,
+            material: PhysicsMaterial::default(),
+            linear_damping: 0.1,
+            angular_damping: 0.1,
+            can_sleep: false,
+        }
+    }
+}
+
+impl Resource for PhysicsConfig {}
+
+/// Rectangle that physics bodies are kept in, and what happens to those
+/// that leave it.
+///
+/// Checked after every physics step for dynamic and kinematic bodies, except
+/// those whose `PhysicsBodyOptions::ignore_bounds` is set. Remove the resource
+/// to let bodies go anywhere. Defaults to bouncing off the edges of the
+/// default screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    /// Top-left corner, in pixels
+    pub min: Vector2<f32>,
+    /// Bottom-right corner, in pixels
+    pub max: Vector2<f32>,
+    /// How far inside the edges body positions are kept, e.g. half a
+    /// sprite; not used by `BoundsBehavior::Colliders`
+    pub margin: f32,
+    pub behavior: BoundsBehavior,
+}
+
+/// What `WorldBounds` does with a body that leaves them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsBehavior {
+    /// Reappear at the opposite edge
+    Wrap,
+    /// Stop at the edge
+    Clamp,
+    /// Move back inside and reverse the velocity component pointing out
+    Bounce,
+    /// Send `OutOfBounds` and despawn the entity
+    Despawn,
+    /// Surround the bounds with fixed colliders, on an entity named
+    /// "world_bounds", that bodies collide with like any other
+    Colliders,
+}
+
+impl WorldBounds {
+    /// Bounds from `min` to `max` with no margin
+    pub fn new(min: Vector2<f32>, max: Vector2<f32>, behavior: BoundsBehavior) -> Self {
+        Self { min, max, margin: 0.0, behavior }
+    }
+
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Corners of the area body positions are kept in
+    pub fn inner(&self) -> (Vector2<f32>, Vector2<f32>) {
+        let margin = Vector2::repeat(self.margin);
+        (self.min + margin, self.max - margin)
+    }
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        let max = Vector2::new(DEFAULT_WIDTH as f32, DEFAULT_HEIGHT as f32);
+        Self::new(Vector2::zeros(), max, BoundsBehavior::Bounce).with_margin(16.0)
+    }
+}
+
+impl Resource for WorldBounds {}
+
+/// Size of the render target in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl ScreenSize {
+    /// Create a new screen size
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    /// Number of pixels in a frame buffer of this size
+    pub fn pixel_count(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+impl Default for ScreenSize {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    }
+}
+
+impl Resource for ScreenSize {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Score(u32);
+    impl Resource for Score {}
+
+    #[test]
+    fn test_insert_replace_and_remove() {
+        let mut resources = Resources::new();
+        assert!(resources.insert(Score(1)).is_none());
+        assert_eq!(resources.insert(Score(2)).map(|s| s.0), Some(1));
+
+        resources.get_mut::<Score>().unwrap().0 += 3;
+        assert_eq!(resources.get::<Score>().map(|s| s.0), Some(5));
+
+        assert_eq!(resources.remove::<Score>().map(|s| s.0), Some(5));
+        assert!(!resources.contains::<Score>());
+        assert_eq!(resources.get_or_insert_with(|| Score(7)).0, 7);
+    }
+
+    #[test]
+    fn test_fixed_time_steps_match_across_frame_rates() {
+        for fps in [30, 60, 144] {
+            let mut fixed = FixedTime::default();
+            let steps: u32 = (0..fps).map(|_| fixed.accumulate(1.0 / fps as f32)).sum();
+            assert_eq!(steps, 60, "{} fps", fps);
+        }
+
+        // A long stall runs at most `max_steps_per_frame` steps and drops the rest
+        let mut fixed = FixedTime::default();
+        assert_eq!(fixed.accumulate(1.005), 5);
+        assert!(fixed.alpha() < 1.0);
+        assert_eq!(fixed.accumulate(1.0 / 60.0), 1);
+    }
+}
@@ -1,24 +1,126 @@
 use serde::{Deserialize, Serialize};
 use rapier2d::prelude::RigidBodyType;
-use crate::components::{Position, Velocity, TextureSprite};
+use crate::components::{CharacterController, ColliderShape, Entity, PhysicsBodyOptions, Position, Velocity, TextureSprite, Transform, Tags};
+use crate::events::SceneLoaded;
+use crate::joints::JointDesc;
+use crate::layers::{CollisionLayers, LayerError, LayerMask};
 use crate::world::World;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Reference from one scene entity to another: by `name`, as scenes are
+/// written by hand, or by `id`, as saved scenes do so that unnamed and
+/// same-named entities can be linked too
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EntityRef {
+    Id(u32),
+    Name(String),
+}
+
+impl From<&str> for EntityRef {
+    fn from(name: &str) -> Self {
+        EntityRef::Name(name.to_string())
+    }
+}
+
+impl From<u32> for EntityRef {
+    fn from(id: u32) -> Self {
+        EntityRef::Id(id)
+    }
+}
+
+impl std::fmt::Display for EntityRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntityRef::Id(id) => write!(f, "#{}", id),
+            EntityRef::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
 
 /// Serializable entity data for scenes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EntityData {
+    /// Scene-local key that `parent` and joints can refer to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
     pub name: Option<String>,
     pub position: Option<Position>,
     pub velocity: Option<Velocity>,
     pub texture_sprite: Option<TextureSprite>,
     pub physics_body: Option<PhysicsBodyData>,
+    /// Moves a kinematic body by desired movement, e.g. the player:
+    /// `character_controller: Some((snap_to_ground: Some(4.0)))`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub character_controller: Option<CharacterController>,
+    /// Parent entity in the same scene, e.g. `Some("tank")` or `Some(3)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<EntityRef>,
+    /// Transform relative to the parent (or the world for root entities)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+    /// Tags for `World::iter_tagged`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 /// Serializable physics body configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhysicsBodyData {
+    /// Side of the square collider, unless `shape` is set
+    #[serde(default)]
     pub size: f32,
     pub body_type: PhysicsBodyType,
+    /// Any other collider, e.g. `shape: Some(Ball(radius: 16.0))`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<ColliderShape>,
+    /// Overrides of the `PhysicsConfig` defaults, e.g.
+    /// `options: (gravity_scale: Some(0.0), lock_rotation: true)`
+    #[serde(default, skip_serializing_if = "PhysicsBodyOptions::is_default")]
+    pub options: PhysicsBodyOptions,
+    /// Collision layers the body is in and touches, named in the project's
+    /// `CollisionLayers`, e.g.
+    /// `collision_layers: Some((member_of: Some(["pickup"]), interacts_with: Some(["player"])))`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision_layers: Option<LayerMask>,
+    /// Layers of the touching bodies that also push this one apart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solver_layers: Option<LayerMask>,
+}
+
+impl PhysicsBodyData {
+    /// Describe a body, writing squares as a plain `size`
+    pub fn new(shape: ColliderShape, body_type: PhysicsBodyType, options: PhysicsBodyOptions) -> Self {
+        let (size, shape) = match shape.square_size() {
+            Some(size) => (size, None),
+            None => (0.0, Some(shape)),
+        };
+        Self { size, body_type, shape, options, collision_layers: None, solver_layers: None }
+    }
+
+    /// Write the options' collision and solver groups as names from `layers`
+    pub fn with_layer_names(mut self, layers: &CollisionLayers) -> Self {
+        self.collision_layers = self.options.collision_groups.take().map(|groups| layers.describe(groups));
+        self.solver_layers = self.options.solver_groups.take().map(|groups| layers.describe(groups));
+        self
+    }
+
+    /// The options this body gets, with its layer names turned into groups
+    pub fn body_options(&self, layers: &CollisionLayers) -> Result<PhysicsBodyOptions, LayerError> {
+        let mut options = self.options;
+        if let Some(mask) = &self.collision_layers {
+            options.collision_groups = Some(layers.resolve(mask)?);
+        }
+        if let Some(mask) = &self.solver_layers {
+            options.solver_groups = Some(layers.resolve(mask)?);
+        }
+        Ok(options)
+    }
+
+    /// The collider this body gets
+    pub fn collider_shape(&self) -> ColliderShape {
+        self.shape.clone().unwrap_or_else(|| ColliderShape::square(self.size))
+    }
 }
 
 /// Serializable version of RigidBodyType for RON files
@@ -30,6 +132,17 @@ pub enum PhysicsBodyType {
     KinematicVelocityBased,
 }
 
+impl From<RigidBodyType> for PhysicsBodyType {
+    fn from(body_type: RigidBodyType) -> Self {
+        match body_type {
+            RigidBodyType::Dynamic => PhysicsBodyType::Dynamic,
+            RigidBodyType::Fixed => PhysicsBodyType::Fixed,
+            RigidBodyType::KinematicPositionBased => PhysicsBodyType::KinematicPositionBased,
+            RigidBodyType::KinematicVelocityBased => PhysicsBodyType::KinematicVelocityBased,
+        }
+    }
+}
+
 impl From<PhysicsBodyType> for RigidBodyType {
     fn from(body_type: PhysicsBodyType) -> Self {
         match body_type {
@@ -41,18 +154,39 @@ impl From<PhysicsBodyType> for RigidBodyType {
     }
 }
 
+/// Serializable joint between two named entities of a scene, e.g.
+/// `JointData(entity_a: "wall", entity_b: "door", joint: (kind: Revolute(), anchor_a: (16.0, 0.0)))`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JointData {
+    pub entity_a: EntityRef,
+    pub entity_b: EntityRef,
+    pub joint: JointDesc,
+}
+
 /// Scene data structure containing all entities
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Scene {
     pub name: String,
     pub description: Option<String>,
     pub entities: Vec<EntityData>,
+    /// Joints between entities with physics bodies, by entity name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub joints: Vec<JointData>,
 }
 
 /// Scene loader for spawning entities from scene files
 pub struct SceneLoader;
 
 impl SceneLoader {
+    /// Load a scene from a `.ron` file, or from JSON for any other extension
+    pub fn load(file_path: &str) -> Result<Scene, Box<dyn std::error::Error>> {
+        if file_path.ends_with(".ron") {
+            Self::load_from_ron(file_path)
+        } else {
+            Self::load_from_json(file_path)
+        }
+    }
+
     /// Load a scene from a RON file
     pub fn load_from_ron(file_path: &str) -> Result<Scene, Box<dyn std::error::Error>> {
         let contents = std::fs::read_to_string(file_path)?;
@@ -82,8 +216,10 @@ impl SceneLoader {
     }
 
     /// Spawn all entities from a scene into the world
-    pub fn spawn_scene(scene: &Scene, world: &mut World) -> HashMap<String, crate::components::Entity> {
+    pub fn spawn_scene(scene: &Scene, world: &mut World) -> HashMap<String, Entity> {
         let mut entity_map = HashMap::new();
+        let mut ids = HashMap::new();
+        let mut spawned = Vec::with_capacity(scene.entities.len());
         
         println!("Loading scene: {}", scene.name);
         if let Some(description) = &scene.description {
@@ -111,12 +247,32 @@ impl SceneLoader {
             // Add physics body if specified
             if let Some(ref physics_data) = entity_data.physics_body {
                 let position = entity_data.position.unwrap_or(Position::new(0.0, 0.0));
-                world.add_physics_body(
+                let layers = world.resource::<CollisionLayers>().cloned().unwrap_or_default();
+                let options = physics_data.body_options(&layers).unwrap_or_else(|e| {
+                    println!("Ignoring collision layers of entity {}: {}", index, e);
+                    physics_data.options
+                });
+                let added = world.add_physics_body_with_shape(
                     entity,
                     position,
-                    physics_data.size,
+                    physics_data.collider_shape(),
                     physics_data.body_type.clone().into(),
+                    options,
                 );
+                if let Err(e) = added {
+                    println!("Skipping physics body of entity {}: {}", index, e);
+                }
+            }
+
+            if let Some(controller) = entity_data.character_controller {
+                world.insert(entity, controller);
+            }
+
+            if let Some(ref name) = entity_data.name {
+                world.set_name(entity, name.clone());
+            }
+            if !entity_data.tags.is_empty() {
+                world.insert(entity, Tags::new(entity_data.tags.iter().cloned()));
             }
 
             // Map entity by name or index
@@ -124,26 +280,127 @@ impl SceneLoader {
                 .clone()
                 .unwrap_or_else(|| format!("entity_{}", index));
             entity_map.insert(entity_name.clone(), entity);
+            if let Some(id) = entity_data.id {
+                ids.insert(id, entity);
+            }
+            spawned.push(entity);
 
             println!("Spawned entity '{}' (ID: {})", entity_name, entity);
         }
 
+        let resolve = |reference: &EntityRef| match reference {
+            EntityRef::Id(id) => ids.get(id).copied(),
+            EntityRef::Name(name) => entity_map.get(name).copied(),
+        };
+
+        // Link parents once every entity exists, so children may be listed before their parent
+        for (entity_data, &entity) in scene.entities.iter().zip(&spawned) {
+            if let Some(ref parent_ref) = entity_data.parent {
+                match resolve(parent_ref) {
+                    Some(parent) => {
+                        world.set_parent(entity, parent);
+                    }
+                    None => println!("Warning: parent {} of entity {} not found in scene", parent_ref, entity),
+                }
+            }
+            if let Some(transform) = entity_data.transform {
+                world.insert(entity, transform);
+            }
+        }
+        world.propagate_transforms();
+
+        // Joints too, once both ends exist
+        for joint in &scene.joints {
+            let (a, b) = (&joint.entity_a, &joint.entity_b);
+            let (Some(entity_a), Some(entity_b)) = (resolve(a), resolve(b)) else {
+                println!("Warning: joint between {} and {} names an entity not in scene", a, b);
+                continue;
+            };
+            if world.add_joint(entity_a, entity_b, joint.joint).is_none() {
+                println!("Warning: joint between {} and {} needs both to have physics bodies", a, b);
+            }
+        }
+
         println!("Scene loaded successfully! Spawned {} entities", scene.entities.len());
+        world.send_event(SceneLoaded {
+            name: scene.name.clone(),
+            entities: spawned,
+        });
         entity_map
     }
 
-    /// Create a scene from current world state (for saving/debugging)
-    pub fn create_scene_from_world(_world: &World, name: String, description: Option<String>) -> Scene {
-        let entities = Vec::new();
+    /// Create a scene from every entity in the world, each root followed by
+    /// its descendants, so that `spawn_scene` rebuilds the same hierarchy.
+    /// The `WorldBounds` edge colliders are left out; they come back with the resource.
+    pub fn create_scene_from_world(world: &World, name: String, description: Option<String>) -> Scene {
+        let bounds = world.bounds_colliders_entity();
+        let mut order = Vec::new();
+        for root in world.query::<Entity>() {
+            if world.parent(root).is_some() || Some(root) == bounds {
+                continue;
+            }
+            order.push(root);
+            order.extend(world.descendants(root));
+        }
+
+        // Parents and joint ends are linked by a scene-local id, their
+        // index in the scene, so names are saved only where they exist
+        let index: HashMap<Entity, u32> = order.iter().enumerate().map(|(i, &entity)| (entity, i as u32)).collect();
+        let mut linked: HashSet<Entity> = order.iter().filter_map(|&entity| world.parent(entity)).collect();
+        let mut joints = Vec::new();
+        for (_, joint) in world.joints() {
+            let (Some(&a), Some(&b)) = (index.get(&joint.entity_a), index.get(&joint.entity_b)) else {
+                continue;
+            };
+            linked.extend([joint.entity_a, joint.entity_b]);
+            joints.push(JointData { entity_a: EntityRef::Id(a), entity_b: EntityRef::Id(b), joint: joint.desc });
+        }
+        let ids: HashMap<Entity, u32> = index.into_iter().filter(|(entity, _)| linked.contains(entity)).collect();
 
-        // Note: This is a simplified version that would need to iterate through all entities
-        // In a real implementation, you'd want to iterate through all entities in the world
-        // For now, this serves as a template for the scene structure
+        let entities = order
+            .iter()
+            .map(|&entity| Self::create_entity_data(world, entity, &ids))
+            .collect();
 
         Scene {
             name,
             description,
             entities,
+            joints,
+        }
+    }
+
+    /// Describe one entity; `ids` maps saved link targets to their scene ids
+    fn create_entity_data(world: &World, entity: Entity, ids: &HashMap<Entity, u32>) -> EntityData {
+        let physics_body = world.entity_to_body.get(&entity).zip(world.get::<ColliderShape>(entity)).map(
+            |(&body_handle, shape)| {
+                let layers = world.resource::<CollisionLayers>().cloned().unwrap_or_default();
+                PhysicsBodyData::new(
+                    shape.clone(),
+                    world.physics_world[body_handle].body_type().into(),
+                    world.get::<PhysicsBodyOptions>(entity).copied().unwrap_or_default(),
+                )
+                .with_layer_names(&layers)
+            },
+        );
+
+        // Children keep their parent link and local transform
+        let parent = world.parent(entity).and_then(|parent| ids.get(&parent)).map(|&id| EntityRef::Id(id));
+
+        EntityData {
+            id: ids.get(&entity).copied(),
+            name: world.name(entity).map(str::to_string),
+            position: world.get_position(entity).copied(),
+            velocity: world.get_velocity(entity).copied(),
+            texture_sprite: world.get_texture_sprite(entity).cloned(),
+            physics_body,
+            character_controller: world.get::<CharacterController>(entity).copied(),
+            parent,
+            transform: world.get::<Transform>(entity).copied(),
+            tags: world
+                .get::<Tags>(entity)
+                .map(|tags| tags.iter().map(str::to_string).collect())
+                .unwrap_or_default(),
         }
     }
 }
@@ -188,6 +445,50 @@ impl<'de> Deserialize<'de> for Velocity {
     }
 }
 
+/// Serialized shape of a `Transform`: tuples like `Position`, rotation in radians
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Transform")]
+struct TransformData {
+    translation: (f32, f32),
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default = "TransformData::unit_scale")]
+    scale: (f32, f32),
+}
+
+impl TransformData {
+    fn unit_scale() -> (f32, f32) {
+        (1.0, 1.0)
+    }
+}
+
+/// Make Transform serializable
+impl Serialize for Transform {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        TransformData {
+            translation: (self.translation.x, self.translation.y),
+            rotation: self.rotation,
+            scale: (self.scale.x, self.scale.y),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Transform {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = TransformData::deserialize(deserializer)?;
+        Ok(Transform::from_xy(data.translation.0, data.translation.1)
+            .with_rotation(data.rotation)
+            .with_scale(data.scale.0, data.scale.1))
+    }
+}
+
 /// Make TextureSprite serializable
 impl Serialize for TextureSprite {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -251,40 +552,58 @@ impl<'de> Deserialize<'de> for TextureSprite {
             }
         }
 
-        const FIELDS: &'static [&'static str] = &["atlas_name", "scale"];
+        const FIELDS: &[&str] = &["atlas_name", "scale"];
         deserializer.deserialize_struct("TextureSprite", FIELDS, TextureSpriteVisitor)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::components::{Name, ShapePart};
     use super::*;
 
     #[test]
     fn test_scene_serialization() {
+        let enemy_shape = ColliderShape::Compound(vec![
+            ShapePart::new((0.0, -8.0), ColliderShape::ball(8.0)),
+            ShapePart::new((0.0, 6.0), ColliderShape::capsule(4.0, 6.0)).with_rotation(1.5),
+        ]);
         let scene = Scene {
             name: "Test Scene".to_string(),
             description: Some("A test scene for unit testing".to_string()),
+            joints: Vec::new(),
             entities: vec![
                 EntityData {
                     name: Some("player".to_string()),
                     position: Some(Position::new(100.0, 100.0)),
                     velocity: Some(Velocity::new(0.0, 0.0)),
                     texture_sprite: Some(TextureSprite::with_scale("player", 2.0)),
-                    physics_body: Some(PhysicsBodyData {
-                        size: 32.0,
-                        body_type: PhysicsBodyType::Dynamic,
-                    }),
+                    physics_body: Some(PhysicsBodyData::new(
+                        ColliderShape::square(32.0),
+                        PhysicsBodyType::Dynamic,
+                        PhysicsBodyOptions::new().with_gravity_scale(0.5).with_locked_rotation(),
+                    )),
+                    ..Default::default()
                 },
                 EntityData {
                     name: Some("enemy".to_string()),
+                    tags: vec!["enemy".to_string(), "hostile".to_string()],
                     position: Some(Position::new(300.0, 200.0)),
                     velocity: Some(Velocity::new(20.0, 15.0)),
                     texture_sprite: Some(TextureSprite::with_name("enemy1")),
-                    physics_body: Some(PhysicsBodyData {
-                        size: 24.0,
-                        body_type: PhysicsBodyType::Dynamic,
-                    }),
+                    physics_body: Some(PhysicsBodyData::new(
+                        enemy_shape.clone(),
+                        PhysicsBodyType::Dynamic,
+                        PhysicsBodyOptions::default(),
+                    )),
+                    ..Default::default()
+                },
+                EntityData {
+                    name: Some("turret".to_string()),
+                    position: Some(Position::new(310.0, 200.0)),
+                    parent: Some("enemy".into()),
+                    transform: Some(Transform::from_xy(10.0, 0.0).with_rotation(0.5)),
+                    ..Default::default()
                 },
             ],
         };
@@ -297,5 +616,167 @@ mod tests {
         let deserialized: Scene = ron::from_str(&ron_string).unwrap();
         assert_eq!(deserialized.name, scene.name);
         assert_eq!(deserialized.entities.len(), scene.entities.len());
+        assert!(deserialized.entities[0].parent.is_none());
+        assert!(deserialized.entities[0].tags.is_empty());
+        assert_eq!(deserialized.entities[1].tags, scene.entities[1].tags);
+        assert_eq!(deserialized.entities[2].parent, Some("enemy".into()));
+        assert_eq!(deserialized.entities[2].transform, scene.entities[2].transform);
+        let physics = |index: usize| deserialized.entities[index].physics_body.as_ref().unwrap().options;
+        assert_eq!(physics(0), PhysicsBodyOptions::new().with_gravity_scale(0.5).with_locked_rotation());
+        assert!(physics(1).is_default());
+        assert_eq!(ron_string.matches("options").count(), 1, "default options are not written");
+        let shape = |index: usize| deserialized.entities[index].physics_body.as_ref().unwrap().collider_shape();
+        assert_eq!(shape(0), ColliderShape::square(32.0));
+        assert_eq!(shape(1), enemy_shape);
+        assert_eq!(ron_string.matches("shape: Some").count(), 1, "squares are written as a size");
+
+        let mut world = World::new();
+        let entities = SceneLoader::spawn_scene(&deserialized, &mut world);
+        assert_eq!(world.get::<ColliderShape>(entities["enemy"]), Some(&enemy_shape));
+    }
+
+    #[test]
+    fn test_spawn_scene_links_hierarchy() {
+        let scene = Scene {
+            name: "Hierarchy".to_string(),
+            description: None,
+            joints: Vec::new(),
+            entities: vec![
+                EntityData {
+                    name: Some("turret".to_string()),
+                    position: Some(Position::new(0.0, 0.0)),
+                    parent: Some("tank".into()),
+                    transform: Some(Transform::from_xy(0.0, -8.0)),
+                    ..Default::default()
+                },
+                EntityData {
+                    name: Some("tank".to_string()),
+                    position: Some(Position::new(100.0, 50.0)),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let mut world = World::new();
+        let entities = SceneLoader::spawn_scene(&scene, &mut world);
+        let (tank, turret) = (entities["tank"], entities["turret"]);
+        assert_eq!(world.parent(turret), Some(tank));
+        assert_eq!(world.get::<Position>(turret).map(|p| p.as_tuple()), Some((100.0, 42.0)));
+
+        // Moving the tank drags the turret along; despawning it takes the turret too
+        world.get_mut::<Position>(tank).unwrap().x = 200.0;
+        world.propagate_transforms();
+        assert_eq!(world.get::<Position>(turret).map(|p| p.as_tuple()), Some((200.0, 42.0)));
+
+        world.remove_entity(tank);
+        assert!(!world.is_alive(turret));
+    }
+
+    #[test]
+    fn test_world_hierarchy_round_trips_through_scene() {
+        let mut world = World::new();
+        let tank = world.spawn((Position::new(100.0, 50.0),));
+        world.set_name(tank, "tank");
+        let turret = world.spawn((Position::new(0.0, 0.0),));
+        world.set_parent(turret, tank);
+        world.insert(turret, Transform::from_xy(0.0, -8.0).with_rotation(0.5));
+        let barrel = world.spawn((Position::new(0.0, 0.0),));
+        world.set_name(barrel, "barrel");
+        world.set_parent(barrel, turret);
+        world.insert(barrel, Transform::from_xy(12.0, 0.0));
+        world.propagate_transforms();
+
+        let scene = SceneLoader::create_scene_from_world(&world, "Tanks".to_string(), None);
+        let ron_string = ron::ser::to_string_pretty(&scene, ron::ser::PrettyConfig::default()).unwrap();
+        let scene: Scene = ron::from_str(&ron_string).unwrap();
+        assert_eq!(scene.entities.len(), 3);
+
+        let mut loaded = World::new();
+        let entities = SceneLoader::spawn_scene(&scene, &mut loaded);
+        let (loaded_tank, loaded_barrel) = (entities["tank"], entities["barrel"]);
+        let loaded_turret = loaded.parent(loaded_barrel).expect("the barrel keeps its parent");
+        assert_eq!(loaded.parent(loaded_turret), Some(loaded_tank));
+        assert_eq!(loaded.get::<Transform>(loaded_turret), world.get::<Transform>(turret));
+        assert_eq!(loaded.get::<Transform>(loaded_barrel), world.get::<Transform>(barrel));
+        let position = |world: &World, entity| world.get::<Position>(entity).map(|p| p.as_tuple());
+        assert_eq!(position(&loaded, loaded_barrel), position(&world, barrel));
+    }
+
+    #[test]
+    fn test_saved_scenes_link_by_id_without_inventing_names() {
+        // One entity is really called what a generated name would have been
+        let mut world = World::new();
+        let named = world.spawn(());
+        let unnamed = world.spawn(());
+        world.set_name(named, format!("entity_{}", unnamed));
+        let child = world.spawn(());
+        world.set_parent(child, unnamed);
+        let twin = world.spawn(());
+        world.set_name(twin, format!("entity_{}", unnamed));
+
+        let scene = SceneLoader::create_scene_from_world(&world, "Names".to_string(), None);
+        let names: Vec<_> = scene.entities.iter().map(|data| data.name.as_deref()).collect();
+        let real = format!("entity_{}", unnamed);
+        assert_eq!(names, vec![Some(real.as_str()), None, None, Some(real.as_str())]);
+        assert_eq!(scene.entities[1].id, Some(1));
+        assert_eq!(scene.entities[2].parent, Some(EntityRef::Id(1)));
+        assert!(scene.entities[0].id.is_none(), "only link targets get an id");
+
+        // Saving what was loaded gives the same scene: no names appear
+        let ron_string = ron::ser::to_string_pretty(&scene, ron::ser::PrettyConfig::default()).unwrap();
+        let mut loaded = World::new();
+        SceneLoader::spawn_scene(&ron::from_str(&ron_string).unwrap(), &mut loaded);
+        let resaved = SceneLoader::create_scene_from_world(&loaded, "Names".to_string(), None);
+        let resaved_ron = ron::ser::to_string_pretty(&resaved, ron::ser::PrettyConfig::default()).unwrap();
+        assert_eq!(resaved_ron, ron_string);
+        let unnamed = loaded.query::<Entity>().nth(1).unwrap();
+        assert!(loaded.name(unnamed).is_none());
+        assert_eq!(loaded.children(unnamed).len(), 1);
+    }
+
+    #[test]
+    fn test_spawn_scene_indexes_names_and_tags() {
+        let scene = Scene {
+            name: "Names".to_string(),
+            description: None,
+            joints: Vec::new(),
+            entities: vec![
+                EntityData {
+                    name: Some("player".to_string()),
+                    ..Default::default()
+                },
+                EntityData {
+                    name: Some("grunt".to_string()),
+                    tags: vec!["enemy".to_string()],
+                    ..Default::default()
+                },
+                EntityData {
+                    tags: vec!["enemy".to_string(), "boss".to_string()],
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let mut world = World::new();
+        SceneLoader::spawn_scene(&scene, &mut world);
+        let player = world.find_by_name("player").unwrap();
+        assert_eq!(world.name(player), Some("player"));
+        assert_eq!(world.iter_tagged("enemy").count(), 2);
+
+        // Renaming, retagging and despawning keep the indices in sync
+        world.set_name(player, "hero");
+        assert_eq!(world.find_by_name("player"), None);
+        assert_eq!(world.find_by_name("hero"), Some(player));
+
+        let grunt = world.find_by_name("grunt").unwrap();
+        world.remove_tag(grunt, "enemy");
+        world.add_tag(player, "enemy");
+        let boss = world.iter_tagged("boss").next().unwrap();
+        world.remove_entity(boss);
+        assert_eq!(world.iter_tagged("enemy").collect::<Vec<_>>(), vec![player]);
+        assert_eq!(world.iter_tagged("boss").count(), 0);
+
+        world.remove::<Name>(player);
+        assert_eq!(world.find_by_name("hero"), None);
     }
 }
@@ -0,0 +1,156 @@
+use nalgebra::Vector2;
+use rapier2d::prelude::InteractionGroups;
+use crate::entity::Entity;
+
+/// Which colliders a physics query such as `World::cast_ray` considers.
+/// The default considers every collider.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpatialFilter {
+    /// Entities whose colliders are skipped, e.g. the one asking
+    pub exclude: Vec<Entity>,
+    pub exclude_sensors: bool,
+    /// Skip colliders that aren't sensors
+    pub exclude_solids: bool,
+    /// Only consider colliders whose collision groups interact with these
+    pub groups: Option<InteractionGroups>,
+}
+
+impl SpatialFilter {
+    /// Consider every collider
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip the colliders of `entity`
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.exclude.push(entity);
+        self
+    }
+
+    /// Skip sensors
+    pub fn solids_only(mut self) -> Self {
+        self.exclude_sensors = true;
+        self
+    }
+
+    /// Skip everything but sensors
+    pub fn sensors_only(mut self) -> Self {
+        self.exclude_solids = true;
+        self
+    }
+
+    /// Only consider colliders whose collision groups interact with `groups`
+    pub fn with_groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+}
+
+/// Where a ray or a cast shape first touches a collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// World-space point on the hit collider
+    pub point: Vector2<f32>,
+    /// Outward surface normal of the hit collider at `point`
+    pub normal: Vector2<f32>,
+    /// How far along the cast the hit is, in multiples of the direction
+    /// vector; 0 if the cast starts inside the collider
+    pub toi: f32,
+}
+
+/// The point of a collider closest to a query point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointProjection {
+    pub entity: Entity,
+    /// World-space point on the collider's surface, or the query point itself
+    /// if that is inside
+    pub point: Vector2<f32>,
+    pub is_inside: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+    use rapier2d::prelude::RigidBodyType;
+    use crate::components::{ColliderShape, PhysicsBodyOptions, Position, Velocity};
+    use crate::events::{CollisionStarted, EventReader, SensorEntered, SensorExited};
+    use crate::world::World;
+    use super::*;
+
+    #[test]
+    fn test_sensors_report_overlaps_and_queries_find_entities() {
+        let mut world = World::new();
+        let pickup = world.spawn(());
+        let sensor = PhysicsBodyOptions::new().as_sensor();
+        world.add_physics_body_with_options(pickup, Position::new(200.0, 100.0), 40.0, RigidBodyType::Fixed, sensor);
+        let player = world.spawn((Position::new(100.0, 100.0), Velocity::new(300.0, 0.0)));
+        world.add_physics_body(player, Position::new(100.0, 100.0), 20.0, RigidBodyType::Dynamic);
+
+        let mut entered = EventReader::<SensorEntered>::new();
+        let mut exited = EventReader::<SensorExited>::new();
+        let mut collisions = EventReader::<CollisionStarted>::new();
+        let entering = SensorEntered { sensor: pickup, entity: player };
+        let mut inside = false;
+        while !inside {
+            world.step_physics();
+            inside = world.read_events(&mut entered).any(|event| *event == entering);
+        }
+        assert_eq!(world.intersections_with(pickup).collect::<Vec<_>>(), vec![player]);
+        assert_eq!(world.intersections_with(player).collect::<Vec<_>>(), vec![pickup]);
+
+        let center = Vector2::new(200.0, 100.0);
+        assert_eq!(world.entities_at_point(center, &SpatialFilter::new()), vec![pickup]);
+        assert!(world.entities_at_point(center, &SpatialFilter::new().solids_only()).is_empty());
+        // A bar from inside the player, across its right edge, into the pickup
+        let right_edge = world.get::<Position>(player).map(|p| Vector2::new(p.x + 10.0, p.y)).unwrap();
+        let probe = ColliderShape::cuboid(20.0, 2.0);
+        let mut found = world.entities_in_shape(right_edge, 0.0, &probe, &SpatialFilter::new()).unwrap();
+        found.sort();
+        assert_eq!(found, vec![pickup, player]);
+        let others = SpatialFilter::new().excluding(player);
+        assert_eq!(world.entities_in_shape(right_edge, 0.0, &probe, &others).unwrap(), vec![pickup]);
+
+        for _ in 0..60 {
+            world.step_physics();
+        }
+        let events: Vec<_> = world.read_events(&mut exited).copied().collect();
+        assert_eq!(events, vec![SensorExited { sensor: pickup, entity: player, removed: false }]);
+        assert_eq!(world.read_events(&mut collisions).count(), 0, "sensors don't collide");
+        assert_eq!(world.intersections_with(pickup).count(), 0);
+    }
+
+    #[test]
+    fn test_casts_hit_nearest_colliders_first() {
+        let mut world = World::new();
+        let near = world.spawn(());
+        world.add_physics_body(near, Position::new(200.0, 100.0), 20.0, RigidBodyType::Fixed);
+        let far = world.spawn(());
+        world.add_physics_body(far, Position::new(300.0, 100.0), 20.0, RigidBodyType::Fixed);
+        world.update_query_pipeline();
+
+        let (eye, right) = (Vector2::new(100.0, 100.0), Vector2::new(1.0, 0.0));
+        let everything = SpatialFilter::new();
+        let hit = world.cast_ray(eye, right, 1000.0, &everything).unwrap();
+        let (point, normal) = (Vector2::new(190.0, 100.0), Vector2::new(-1.0, 0.0));
+        assert_eq!(hit, RayHit { entity: near, point, normal, toi: 90.0 });
+        assert!(world.cast_ray(eye, right, 50.0, &everything).is_none());
+        let hits = world.cast_ray_all(eye, right, 1000.0, &everything);
+        assert_eq!(hits.iter().map(|hit| (hit.entity, hit.toi)).collect::<Vec<_>>(), vec![(near, 90.0), (far, 190.0)]);
+        let past_near = SpatialFilter::new().excluding(near);
+        assert_eq!(world.cast_ray(eye, right, 1000.0, &past_near).map(|hit| hit.entity), Some(far));
+
+        let ball = ColliderShape::ball(5.0);
+        let hit = world.cast_shape(eye, 0.0, right, &ball, 1000.0, &everything).unwrap().unwrap();
+        assert_eq!(hit.entity, near);
+        assert!((hit.toi - 85.0).abs() < 1e-3 && (hit.point.x - 190.0).abs() < 1e-3);
+        assert!((hit.normal - Vector2::new(-1.0, 0.0)).norm() < 1e-3);
+        let hits = world.cast_shape_all(eye, 0.0, right, &ball, 1000.0, &everything).unwrap();
+        assert_eq!(hits.iter().map(|hit| hit.entity).collect::<Vec<_>>(), vec![near, far]);
+
+        let closest = world.project_point(eye, &everything).unwrap();
+        assert_eq!((closest.entity, closest.point, closest.is_inside), (near, Vector2::new(190.0, 100.0), false));
+        let inside = world.project_point(Vector2::new(300.0, 100.0), &everything).unwrap();
+        assert_eq!((inside.entity, inside.is_inside), (far, true));
+    }
+}
@@ -0,0 +1,227 @@
+use std::any::{Any, TypeId};
+use std::fmt::Debug;
+use crate::entity::Entity;
+use crate::resources::Resource;
+use crate::storage::Component;
+use crate::world::World;
+
+/// Values of a state machine, usually a fieldless enum of game screens.
+/// Insert a `States<S>` resource to drive it.
+pub trait State: Copy + Eq + Debug + Send + Sync + 'static {}
+
+/// Current value of state machine `S`, plus at most one queued transition.
+///
+/// The `Scheduler` applies the queued transition once per frame, right
+/// after `PreUpdate`: `on_exit` systems of the old state run, entities
+/// `StateScoped` to it are despawned, then `on_enter` systems of the new
+/// state run. `on_enter` systems of the initial state run on the first frame.
+#[derive(Debug, Clone)]
+pub struct States<S: State> {
+    current: S,
+    previous: Option<S>,
+    queued: Option<S>,
+    /// Whether the initial state has been entered
+    entered: bool,
+}
+
+impl<S: State> States<S> {
+    /// Start in `initial`
+    pub fn new(initial: S) -> Self {
+        Self {
+            current: initial,
+            previous: None,
+            queued: None,
+            entered: false,
+        }
+    }
+
+    /// The state systems currently see
+    pub fn current(&self) -> S {
+        self.current
+    }
+
+    /// Whether the current state is `state`
+    pub fn is(&self, state: S) -> bool {
+        self.current == state
+    }
+
+    /// The state before the last transition
+    pub fn previous(&self) -> Option<S> {
+        self.previous
+    }
+
+    /// The transition waiting for the next frame, if any
+    pub fn queued(&self) -> Option<S> {
+        self.queued
+    }
+
+    /// Queue a transition to `next`, replacing any transition already
+    /// queued. Queuing the current state cancels a pending transition.
+    pub fn set(&mut self, next: S) {
+        self.queued = (next != self.current).then_some(next);
+    }
+
+    /// Apply the queued transition, returning the exited and entered states
+    fn take_transition(&mut self) -> Option<(Option<S>, S)> {
+        if !self.entered {
+            self.entered = true;
+            if self.queued.is_none() {
+                return Some((None, self.current));
+            }
+        }
+        let next = self.queued.take()?;
+        let exited = std::mem::replace(&mut self.current, next);
+        self.previous = Some(exited);
+        Some((Some(exited), next))
+    }
+}
+
+impl<S: State> Resource for States<S> {}
+
+/// Despawns its entity (and descendants) when state machine `S` leaves
+/// the given state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateScoped<S: State>(pub S);
+
+impl<S: State> Component for StateScoped<S> {}
+
+/// A transition that was just applied, with the state types erased
+pub(crate) struct Transition {
+    pub(crate) exited: Option<Box<dyn Any>>,
+    pub(crate) entered: Box<dyn Any>,
+}
+
+/// Type-erased transition logic for one state machine
+#[derive(Clone, Copy)]
+pub(crate) struct StateDriver {
+    pub(crate) state: TypeId,
+    pub(crate) apply: fn(&mut World) -> Option<Transition>,
+    pub(crate) despawn_scoped: fn(&mut World, &dyn Any),
+}
+
+impl StateDriver {
+    pub(crate) fn of<S: State>() -> Self {
+        Self {
+            state: TypeId::of::<S>(),
+            apply: apply_transition::<S>,
+            despawn_scoped: despawn_scoped::<S>,
+        }
+    }
+}
+
+fn apply_transition<S: State>(world: &mut World) -> Option<Transition> {
+    let (exited, entered) = world.resource_mut::<States<S>>()?.take_transition()?;
+    Some(Transition {
+        exited: exited.map(|state| Box::new(state) as Box<dyn Any>),
+        entered: Box::new(entered),
+    })
+}
+
+fn despawn_scoped<S: State>(world: &mut World, exited: &dyn Any) {
+    let Some(&exited) = exited.downcast_ref::<S>() else {
+        return;
+    };
+    let doomed: Vec<Entity> = world.query::<(Entity, &StateScoped<S>)>()
+        .filter(|(_, scoped)| scoped.0 == exited)
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in doomed {
+        world.remove_entity(entity);
+    }
+}
+
+/// Makes a system run only when state machine `state` enters or exits one value
+pub(crate) struct StateHook {
+    pub(crate) state: TypeId,
+    pub(crate) on_enter: bool,
+    value: Box<dyn Any>,
+    equals: fn(&dyn Any, &dyn Any) -> bool,
+}
+
+impl StateHook {
+    pub(crate) fn new<S: State>(value: S, on_enter: bool) -> Self {
+        Self {
+            state: TypeId::of::<S>(),
+            on_enter,
+            value: Box::new(value),
+            equals: |a, b| a.downcast_ref::<S>().is_some_and(|a| b.downcast_ref::<S>() == Some(a)),
+        }
+    }
+
+    /// Whether the hook fires for this transition of its state machine
+    pub(crate) fn fires(&self, transition: &Transition) -> bool {
+        let state = if self.on_enter { Some(&*transition.entered) } else { transition.exited.as_deref() };
+        state.is_some_and(|state| (self.equals)(&*self.value, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::Position;
+    use crate::systems::{Scheduler, Stage, System};
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Screen {
+        Menu,
+        Playing,
+        GameOver,
+    }
+
+    impl State for Screen {}
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    impl Resource for Log {}
+
+    /// Logs its name and, when entering play, spawns a state-scoped entity
+    struct Step(&'static str);
+
+    impl System for Step {
+        fn update(&mut self, world: &mut World, _dt: f32) {
+            world.init_resource::<Log>().0.push(self.0);
+            if self.0 == "EnterPlaying" {
+                world.spawn((Position::new(0.0, 0.0), StateScoped(Screen::Playing)));
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_transitions_run_hooks_and_despawn_scoped_entities() {
+        let mut world = World::new();
+        world.insert_resource(States::new(Screen::Menu));
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Step("Play"))).on_update(Screen::Playing);
+        scheduler.add_system(Box::new(Step("EnterMenu"))).on_enter(Screen::Menu);
+        scheduler.add_system(Box::new(Step("ExitMenu"))).on_exit(Screen::Menu);
+        scheduler.add_system(Box::new(Step("EnterPlaying"))).on_enter(Screen::Playing);
+        scheduler.add_system(Box::new(Step("ExitPlaying"))).on_exit(Screen::Playing);
+        scheduler.add_system(Box::new(Step("Input"))).in_stage(Stage::PreUpdate);
+        let frame = |scheduler: &mut Scheduler, world: &mut World| {
+            scheduler.update(world, 0.1);
+            std::mem::take(&mut world.resource_mut::<Log>().unwrap().0)
+        };
+
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Input", "EnterMenu"]);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Input"]);
+
+        // Queued transitions wait for the next frame, after PreUpdate
+        let states = world.resource_mut::<States<Screen>>().unwrap();
+        states.set(Screen::Playing);
+        assert_eq!((states.current(), states.queued()), (Screen::Menu, Some(Screen::Playing)));
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Input", "ExitMenu", "EnterPlaying", "Play"]);
+        assert_eq!(world.query::<&StateScoped<Screen>>().count(), 1);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Input", "Play"]);
+
+        world.resource_mut::<States<Screen>>().unwrap().set(Screen::GameOver);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Input", "ExitPlaying"]);
+        assert_eq!(world.query::<&StateScoped<Screen>>().count(), 0);
+        let states = world.resource::<States<Screen>>().unwrap();
+        assert_eq!((states.current(), states.previous()), (Screen::GameOver, Some(Screen::Playing)));
+    }
+}
@@ -0,0 +1,603 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use crate::change_detection::{ComponentTicks, Removed, Tick};
+use crate::entity::Entity;
+use crate::events::Events;
+use crate::world::World;
+
+/// Marker trait for data that can be attached to entities.
+///
+/// Game crates implement this for their own types (`impl Component for Health {}`)
+/// and store them with `World::insert` without touching the engine.
+pub trait Component: Send + Sync + 'static {}
+
+/// A set of components inserted together, e.g. `(Position, Velocity)`
+pub trait Bundle: Send + 'static {
+    /// Insert every component of the bundle on an entity
+    fn insert_into(self, world: &mut World, entity: Entity);
+}
+
+macro_rules! impl_bundle_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables)]
+        impl<$($name: Component),*> Bundle for ($($name,)*) {
+            fn insert_into(self, world: &mut World, entity: Entity) {
+                let ($($name,)*) = self;
+                $(world.insert(entity, $name);)*
+            }
+        }
+    };
+}
+
+impl_bundle_tuple!();
+impl_bundle_tuple!(A);
+impl_bundle_tuple!(A, B);
+impl_bundle_tuple!(A, B, C);
+impl_bundle_tuple!(A, B, C, D);
+impl_bundle_tuple!(A, B, C, D, E);
+impl_bundle_tuple!(A, B, C, D, E, F);
+impl_bundle_tuple!(A, B, C, D, E, F, G);
+impl_bundle_tuple!(A, B, C, D, E, F, G, H);
+
+/// Sparse-set storage for a single component type.
+///
+/// Components are packed densely for fast iteration; `sparse` maps an entity's
+/// slot index to its position in the dense arrays. Each component carries the
+/// ticks it was added and last changed at.
+pub struct ComponentStorage<T: Component> {
+    sparse: Vec<Option<u32>>,
+    entities: Vec<Entity>,
+    data: Vec<T>,
+    ticks: Vec<ComponentTicks>,
+    removed: Events<Removed>,
+}
+
+impl<T: Component> ComponentStorage<T> {
+    /// Create an empty storage
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            data: Vec::new(),
+            ticks: Vec::new(),
+            removed: Events::new(),
+        }
+    }
+
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let dense = (*self.sparse.get(entity.index() as usize)?)? as usize;
+        (self.entities[dense] == entity).then_some(dense)
+    }
+
+    /// Insert a component at `tick`, returning the previous value for this entity
+    pub fn insert(&mut self, entity: Entity, component: T, tick: Tick) -> Option<T> {
+        let slot = entity.index() as usize;
+        if slot >= self.sparse.len() {
+            self.sparse.resize(slot + 1, None);
+        }
+
+        if let Some(dense) = self.sparse[slot] {
+            let dense = dense as usize;
+            if self.entities[dense] == entity {
+                self.ticks[dense].changed = tick;
+                return Some(std::mem::replace(&mut self.data[dense], component));
+            }
+            // The slot still holds data of an older generation: that entity
+            // loses its component, and the new one had none before
+            let stale = std::mem::replace(&mut self.entities[dense], entity);
+            self.removed.send(Removed(stale));
+            self.ticks[dense] = ComponentTicks::new(tick);
+            self.data[dense] = component;
+            return None;
+        }
+
+        self.sparse[slot] = Some(self.data.len() as u32);
+        self.entities.push(entity);
+        self.data.push(component);
+        self.ticks.push(ComponentTicks::new(tick));
+        None
+    }
+
+    /// Remove the component of an entity
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let dense = self.dense_index(entity)?;
+        self.sparse[entity.index() as usize] = None;
+
+        let last = self.data.len() - 1;
+        if dense != last {
+            let moved = self.entities[last];
+            self.sparse[moved.index() as usize] = Some(dense as u32);
+        }
+        self.entities.swap_remove(dense);
+        self.ticks.swap_remove(dense);
+        self.removed.send(Removed(entity));
+        Some(self.data.swap_remove(dense))
+    }
+
+    /// Get the component of an entity
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.dense_index(entity).map(|dense| &self.data[dense])
+    }
+
+    /// Get the component of an entity mutably, marking it changed at `tick`
+    pub fn get_mut(&mut self, entity: Entity, tick: Tick) -> Option<&mut T> {
+        let dense = self.dense_index(entity)?;
+        self.ticks[dense].changed = tick;
+        Some(&mut self.data[dense])
+    }
+
+    /// Added/changed ticks of an entity's component
+    pub fn ticks(&self, entity: Entity) -> Option<ComponentTicks> {
+        self.dense_index(entity).map(|dense| self.ticks[dense])
+    }
+
+    /// Check whether an entity has this component
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    /// Number of stored components
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Entities that own a component, in dense order
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Raw view for queries that hand out component borrows while still
+    /// looking up entities. Data and ticks may be written through it.
+    pub(crate) fn raw_mut(&mut self) -> RawStorage<'_, T> {
+        RawStorage {
+            sparse: self.sparse.as_ptr(),
+            sparse_len: self.sparse.len(),
+            entities: self.entities.as_ptr(),
+            len: self.entities.len(),
+            data: self.data.as_mut_ptr(),
+            ticks: self.ticks.as_mut_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Raw view for change filters that may run alongside a `raw_mut` view
+    /// of the same storage. Nothing may be written through it.
+    pub(crate) fn raw(&self) -> RawStorage<'_, T> {
+        RawStorage {
+            sparse: self.sparse.as_ptr(),
+            sparse_len: self.sparse.len(),
+            entities: self.entities.as_ptr(),
+            len: self.entities.len(),
+            data: self.data.as_ptr() as *mut T,
+            ticks: self.ticks.as_ptr() as *mut ComponentTicks,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Removal records, double-buffered like events
+    pub(crate) fn removed(&self) -> &Events<Removed> {
+        &self.removed
+    }
+
+    /// Iterate over `(entity, component)` pairs
+    pub fn iter(&self) -> StorageIter<'_, T> {
+        StorageIter {
+            entities: self.entities.iter(),
+            data: self.data.iter(),
+        }
+    }
+
+    /// Iterate mutably over `(entity, component)` pairs, marking every
+    /// component changed at `tick`
+    pub fn iter_mut(&mut self, tick: Tick) -> StorageIterMut<'_, T> {
+        for ticks in &mut self.ticks {
+            ticks.changed = tick;
+        }
+        StorageIterMut {
+            entities: self.entities.iter(),
+            data: self.data.iter_mut(),
+        }
+    }
+}
+
+impl<T: Component> Default for ComponentStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pointers into the dense arrays of a storage borrowed for 'w.
+///
+/// Built from `Vec::as_ptr`/`as_mut_ptr`, which never create a reference to
+/// the buffers, so a `&mut T` fetch and an `Added`/`Changed` filter on the
+/// same storage can coexist without a `&` and a `&mut` aliasing.
+pub(crate) struct RawStorage<'w, T> {
+    sparse: *const Option<u32>,
+    sparse_len: usize,
+    entities: *const Entity,
+    len: usize,
+    data: *mut T,
+    ticks: *mut ComponentTicks,
+    _marker: PhantomData<&'w mut T>,
+}
+
+impl<'w, T> RawStorage<'w, T> {
+    /// Entities that own a component, in dense order
+    pub(crate) fn entities(&self) -> &'w [Entity] {
+        // SAFETY: the entity list is never written while the storage is borrowed
+        unsafe { std::slice::from_raw_parts(self.entities, self.len) }
+    }
+
+    /// Position of an entity's component in the dense arrays
+    pub(crate) fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let slot = entity.index() as usize;
+        if slot >= self.sparse_len {
+            return None;
+        }
+        // SAFETY: slot < sparse_len, and the sparse map is never written while borrowed
+        let dense = unsafe { self.sparse.add(slot).read() }? as usize;
+        (self.entities()[dense] == entity).then_some(dense)
+    }
+
+    /// Copy of the ticks at a dense index
+    pub(crate) fn ticks(&self, dense: usize) -> ComponentTicks {
+        assert!(dense < self.len);
+        // SAFETY: in bounds; writers only touch the ticks of entities they
+        // fetched, and each entity is filtered before it is fetched
+        unsafe { self.ticks.add(dense).read() }
+    }
+
+    /// Component and ticks at a dense index.
+    ///
+    /// # Safety
+    /// The view must come from `raw_mut`, `dense` must be in bounds, and no
+    /// other live reference to this component may exist.
+    pub(crate) unsafe fn get_mut(&self, dense: usize) -> (&'w mut T, &'w mut ComponentTicks) {
+        unsafe { (&mut *self.data.add(dense), &mut *self.ticks.add(dense)) }
+    }
+}
+
+/// Iterator over the components of a single storage
+pub struct StorageIter<'a, T> {
+    entities: std::slice::Iter<'a, Entity>,
+    data: std::slice::Iter<'a, T>,
+}
+
+impl<T> StorageIter<'_, T> {
+    /// An iterator that yields nothing (used when a storage was never registered)
+    pub fn empty() -> Self {
+        Self {
+            entities: [].iter(),
+            data: [].iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for StorageIter<'a, T> {
+    type Item = (Entity, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((*self.entities.next()?, self.data.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.data.size_hint()
+    }
+}
+
+/// Mutable iterator over the components of a single storage
+pub struct StorageIterMut<'a, T> {
+    entities: std::slice::Iter<'a, Entity>,
+    data: std::slice::IterMut<'a, T>,
+}
+
+impl<T> StorageIterMut<'_, T> {
+    /// An iterator that yields nothing (used when a storage was never registered)
+    pub fn empty() -> Self {
+        Self {
+            entities: [].iter(),
+            data: [].iter_mut(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for StorageIterMut<'a, T> {
+    type Item = (Entity, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((*self.entities.next()?, self.data.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.data.size_hint()
+    }
+}
+
+/// Type-erased view of a `ComponentStorage<T>`
+pub trait AnyStorage: Send + Sync {
+    /// Drop the component of an entity if present
+    fn remove_entity(&mut self, entity: Entity) -> bool;
+    /// Check whether an entity has this component
+    fn contains(&self, entity: Entity) -> bool;
+    /// Number of stored components
+    fn len(&self) -> usize;
+    /// Whether the storage is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Name of the stored component type, for debugging
+    fn component_name(&self) -> &'static str;
+    /// Frame boundary: drop removal records older than one frame
+    fn update_removed(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Component> AnyStorage for ComponentStorage<T> {
+    fn remove_entity(&mut self, entity: Entity) -> bool {
+        self.remove(entity).is_some()
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        ComponentStorage::contains(self, entity)
+    }
+
+    fn len(&self) -> usize {
+        ComponentStorage::len(self)
+    }
+
+    fn component_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn update_removed(&mut self) {
+        self.removed.update();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Registry of component storages keyed by component type, plus the change
+/// ticks used to stamp inserts and mutable access
+pub struct Components {
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+    change_tick: Tick,
+    last_change_tick: Tick,
+}
+
+impl Components {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            storages: HashMap::new(),
+            change_tick: 1,
+            last_change_tick: 0,
+        }
+    }
+
+    /// Empty registry stamping with the given ticks, used to lend storages
+    /// to systems running in parallel
+    pub(crate) fn with_ticks(change_tick: Tick, last_change_tick: Tick) -> Self {
+        Self {
+            storages: HashMap::new(),
+            change_tick,
+            last_change_tick,
+        }
+    }
+
+    /// Move a storage out of the registry
+    pub(crate) fn take_storage(&mut self, type_id: TypeId) -> Option<Box<dyn AnyStorage>> {
+        self.storages.remove(&type_id)
+    }
+
+    /// Put a storage taken with `take_storage` into this registry
+    pub(crate) fn insert_storage(&mut self, type_id: TypeId, storage: Box<dyn AnyStorage>) {
+        self.storages.insert(type_id, storage);
+    }
+
+    /// Move every storage of `other` back into the registry
+    pub(crate) fn restore_storages(&mut self, other: Components) {
+        self.storages.extend(other.storages);
+    }
+
+    /// Tick stamped on components inserted or mutated now
+    pub fn change_tick(&self) -> Tick {
+        self.change_tick
+    }
+
+    /// Advance the change tick, returning the new value
+    pub fn increment_change_tick(&mut self) -> Tick {
+        self.change_tick += 1;
+        self.change_tick
+    }
+
+    /// Tick that `Added`/`Changed` filters compare against (the running
+    /// system's previous run)
+    pub fn last_change_tick(&self) -> Tick {
+        self.last_change_tick
+    }
+
+    /// Set the tick that `Added`/`Changed` filters compare against
+    pub fn set_last_change_tick(&mut self, tick: Tick) {
+        self.last_change_tick = tick;
+    }
+
+    /// Insert a component, stamping it with the current change tick
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
+        let tick = self.change_tick;
+        self.register::<T>().insert(entity, component, tick)
+    }
+
+    /// Register a component type, creating its storage if needed
+    pub fn register<T: Component>(&mut self) -> &mut ComponentStorage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(ComponentStorage::<T>::new()))
+            .as_any_mut()
+            .downcast_mut::<ComponentStorage<T>>()
+            .expect("component storage registered under the wrong type")
+    }
+
+    /// Check whether a component type has been registered
+    pub fn is_registered<T: Component>(&self) -> bool {
+        self.storages.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Check whether an entity has a component, by component type id
+    pub(crate) fn contains_type(&self, type_id: TypeId, entity: Entity) -> bool {
+        self.storages.get(&type_id).is_some_and(|storage| storage.contains(entity))
+    }
+
+    /// Get the storage for a component type
+    pub fn storage<T: Component>(&self) -> Option<&ComponentStorage<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any().downcast_ref())
+    }
+
+    /// Get the storage for a component type mutably
+    pub fn storage_mut<T: Component>(&mut self) -> Option<&mut ComponentStorage<T>> {
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any_mut().downcast_mut())
+    }
+
+    /// Get the component of an entity
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.storage::<T>()?.get(entity)
+    }
+
+    /// Get the component of an entity mutably, marking it changed
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        let tick = self.change_tick;
+        self.storage_mut::<T>()?.get_mut(entity, tick)
+    }
+
+    /// Iterate over all entities with a component
+    pub fn iter<T: Component>(&self) -> StorageIter<'_, T> {
+        self.storage::<T>().map_or_else(StorageIter::empty, ComponentStorage::iter)
+    }
+
+    /// Iterate mutably over all entities with a component, marking them changed
+    pub fn iter_mut<T: Component>(&mut self) -> StorageIterMut<'_, T> {
+        let tick = self.change_tick;
+        match self.storage_mut::<T>() {
+            Some(storage) => storage.iter_mut(tick),
+            None => StorageIterMut::empty(),
+        }
+    }
+
+    /// Remove every component owned by an entity
+    pub fn remove_entity(&mut self, entity: Entity) {
+        for storage in self.storages.values_mut() {
+            storage.remove_entity(entity);
+        }
+    }
+
+    /// Frame boundary: drop removal records older than one frame
+    pub fn update_removed(&mut self) {
+        for storage in self.storages.values_mut() {
+            storage.update_removed();
+        }
+    }
+
+    /// Names of the component types stored on an entity
+    pub fn component_names(&self, entity: Entity) -> Vec<&'static str> {
+        self.storages
+            .values()
+            .filter(|storage| storage.contains(entity))
+            .map(|storage| storage.component_name())
+            .collect()
+    }
+
+    /// Number of registered component types
+    pub fn len(&self) -> usize {
+        self.storages.len()
+    }
+
+    /// Whether no component type has been registered
+    pub fn is_empty(&self) -> bool {
+        self.storages.is_empty()
+    }
+}
+
+impl Default for Components {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::EventReader;
+    use super::*;
+
+    struct Health(u32);
+    impl Component for Health {}
+
+    #[test]
+    fn test_swap_remove_keeps_sparse_map_consistent() {
+        let mut storage = ComponentStorage::new();
+        let a = Entity::from_raw_parts(0, 0);
+        let b = Entity::from_raw_parts(1, 0);
+        let c = Entity::from_raw_parts(2, 0);
+        storage.insert(a, Health(10), 1);
+        storage.insert(b, Health(20), 1);
+        storage.insert(c, Health(30), 1);
+
+        assert_eq!(storage.remove(a).map(|h| h.0), Some(10));
+        assert_eq!(storage.get(c).map(|h| h.0), Some(30));
+        assert_eq!(storage.get(b).map(|h| h.0), Some(20));
+        assert!(storage.get(a).is_none());
+        assert_eq!(storage.len(), 2);
+    }
+
+    #[test]
+    fn test_stale_generation_is_rejected() {
+        let mut components = Components::new();
+        let old = Entity::from_raw_parts(3, 0);
+        let new = Entity::from_raw_parts(3, 1);
+        components.insert(old, Health(1));
+
+        assert!(components.get::<Health>(new).is_none());
+        components.remove_entity(old);
+        assert!(components.get::<Health>(old).is_none());
+        assert_eq!(components.storage::<Health>().map(|s| s.len()), Some(0));
+
+        // Inserting over a stale generation removes the old value instead of returning it
+        components.insert(old, Health(1));
+        assert!(components.insert(new, Health(2)).is_none());
+        assert!(components.get::<Health>(old).is_none());
+        assert_eq!(components.get::<Health>(new).map(|h| h.0), Some(2));
+        let removed = components.storage::<Health>().unwrap().removed();
+        let removed: Vec<Entity> = EventReader::new().read(removed).map(|r: &Removed| r.0).collect();
+        assert_eq!(removed, vec![old, old]);
+    }
+
+    #[test]
+    fn test_ticks_track_insert_and_mutation() {
+        let mut components = Components::new();
+        let entity = Entity::from_raw_parts(0, 0);
+        components.insert(entity, Health(1));
+        let ticks = components.storage::<Health>().and_then(|s| s.ticks(entity)).unwrap();
+        assert_eq!(ticks, ComponentTicks::new(1));
+
+        components.increment_change_tick();
+        components.get_mut::<Health>(entity).unwrap().0 = 2;
+        let ticks = components.storage::<Health>().and_then(|s| s.ticks(entity)).unwrap();
+        assert!(!ticks.is_added(1));
+        assert!(ticks.is_changed(1));
+    }
+}
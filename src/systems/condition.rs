@@ -0,0 +1,45 @@
+use crate::resources::{Resource, Time};
+use crate::state::{State, States};
+use crate::world::World;
+
+/// Decides each time a system is due whether it runs; see `SystemConfig::run_if`
+pub type RunCondition = Box<dyn FnMut(&World) -> bool>;
+
+/// True while resource `R` exists
+pub fn resource_exists<R: Resource>() -> impl Fn(&World) -> bool + 'static {
+    |world: &World| world.resource::<R>().is_some()
+}
+
+/// True while resource `R` exists and equals `value`,
+/// e.g. `resource_equals(GameState::Playing)`
+pub fn resource_equals<R: Resource + PartialEq>(value: R) -> impl Fn(&World) -> bool + 'static {
+    move |world: &World| world.resource::<R>() == Some(&value)
+}
+
+/// True while state machine `S` is in `state`
+pub fn in_state<S: State>(state: S) -> impl Fn(&World) -> bool + 'static {
+    move |world: &World| world.resource::<States<S>>().is_some_and(|states| states.is(state))
+}
+
+/// True once every `seconds` of `Time::elapsed`, starting with the first check.
+///
+/// Intervals missed between checks fire only once, and later checks stay
+/// on the original beat.
+pub fn on_timer(seconds: f32) -> impl FnMut(&World) -> bool + 'static {
+    let mut next: Option<f32> = None;
+    move |world: &World| {
+        let elapsed = world.resource::<Time>().map_or(0.0, |time| time.elapsed);
+        let due = *next.get_or_insert(elapsed);
+        if elapsed < due {
+            return false;
+        }
+        let missed = if seconds > 0.0 { ((elapsed - due) / seconds).floor() } else { 0.0 };
+        next = Some(due + (missed + 1.0) * seconds);
+        true
+    }
+}
+
+/// Invert a condition
+pub fn not(mut condition: impl FnMut(&World) -> bool + 'static) -> impl FnMut(&World) -> bool + 'static {
+    move |world: &World| !condition(world)
+}
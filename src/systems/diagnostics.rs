@@ -0,0 +1,44 @@
+use crate::resources::Resource;
+use crate::systems::System;
+use crate::world::World;
+
+/// Frame rate and world size, refreshed every frame by `DiagnosticsSystem`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Diagnostics {
+    /// Frames per second, smoothed over roughly the last half second
+    pub fps: f32,
+    /// Seconds the last frame took
+    pub frame_time: f32,
+    pub entity_count: usize,
+    pub body_count: usize,
+}
+
+impl Resource for Diagnostics {}
+
+/// Debug overlay data source. Has no run conditions, so it keeps updating
+/// while gameplay systems are paused.
+pub struct DiagnosticsSystem;
+
+impl DiagnosticsSystem {
+    pub fn new() -> Self { Self }
+}
+
+impl System for DiagnosticsSystem {
+    fn update(&mut self, world: &mut World, dt: f32) {
+        let entity_count = world.entity_count();
+        let body_count = world.physics_world.len();
+        let diagnostics = world.init_resource::<Diagnostics>();
+        if dt > 0.0 {
+            let blend = (dt / 0.5).min(1.0);
+            let fps = if diagnostics.fps > 0.0 { diagnostics.fps } else { 1.0 / dt };
+            diagnostics.fps = fps + (1.0 / dt - fps) * blend;
+        }
+        diagnostics.frame_time = dt;
+        diagnostics.entity_count = entity_count;
+        diagnostics.body_count = body_count;
+    }
+
+    fn name(&self) -> &'static str { "DiagnosticsSystem" }
+}
+
+impl Default for DiagnosticsSystem { fn default() -> Self { Self::new() } }
@@ -1,23 +1,75 @@
-use crate::components::Entity;
+use std::collections::HashSet;
+use crate::components::{CharacterController, Entity};
+use crate::resources::Resource;
 use crate::world::World;
 use crate::systems::System;
-use minifb::Window;
+use minifb::{Key, Window};
+use nalgebra::Vector2;
 
-/// Input System - handles keyboard input
+/// Keyboard state for the current frame, stored as a world resource.
+///
+/// The main loop refreshes it from the window each frame; systems read it
+/// with `world.resource::<InputState>()`.
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    pressed: HashSet<Key>,
+    previous: HashSet<Key>,
+}
+
+impl InputState {
+    /// Create an empty input state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the pressed keys, remembering last frame's keys for edge detection
+    pub fn set_keys(&mut self, keys: impl IntoIterator<Item = Key>) {
+        self.previous = std::mem::take(&mut self.pressed);
+        self.pressed.extend(keys);
+    }
+
+    /// Capture the keys currently held down in a window
+    pub fn update_from_window(&mut self, window: &Window) {
+        self.set_keys(window.get_keys());
+    }
+
+    /// Whether a key is held down
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Whether a key went down this frame
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.pressed.contains(&key) && !self.previous.contains(&key)
+    }
+
+    /// Whether a key was released this frame
+    pub fn just_released(&self, key: Key) -> bool {
+        !self.pressed.contains(&key) && self.previous.contains(&key)
+    }
+}
+
+impl Resource for InputState {}
+
+/// Input System - moves the player from the `InputState` resource, through
+/// its `CharacterController` if it has one
 pub struct InputSystem {
     pub player_entity: Entity,
+    /// When set, the player is looked up by `Name` whenever `player_entity`
+    /// is not alive, e.g. before the scene is spawned or after a respawn
+    pub player_name: Option<&'static str>,
+    pub speed: f32,
 }
 
 impl InputSystem {
     /// Create a new input system
     pub fn new(player_entity: Entity) -> Self {
-        Self { player_entity }
+        Self { player_entity, player_name: None, speed: 150.0 }
     }
 
-    /// Set input state (kept for compatibility)
-    pub fn set_input(&mut self, _window: &Window) {
-        // Input is now handled in the main loop directly
-        // This method is kept for interface compatibility
+    /// Create an input system driving the entity named `name`
+    pub fn for_player_named(name: &'static str) -> Self {
+        Self { player_name: Some(name), ..Self::new(Entity::PLACEHOLDER) }
     }
 
     /// Get the player entity
@@ -32,9 +84,37 @@ impl InputSystem {
 }
 
 impl System for InputSystem {
-    fn update(&mut self, _world: &mut World, _dt: f32) {
-        // Input is handled separately since it needs window reference
-        // The main loop handles input directly to avoid borrowing issues
+    fn update(&mut self, world: &mut World, _dt: f32) {
+        if let Some(name) = self.player_name
+            && !world.is_alive(self.player_entity)
+            && let Some(player) = world.find_by_name(name)
+        {
+            self.player_entity = player;
+        }
+
+        let Some(input) = world.resource::<InputState>() else {
+            return;
+        };
+
+        // Arrow keys drive the player's character controller, or else its physics body
+        let mut velocity = Vector2::zeros();
+        if input.is_pressed(Key::Left) {
+            velocity.x = -self.speed;
+        }
+        if input.is_pressed(Key::Right) {
+            velocity.x = self.speed;
+        }
+        if input.is_pressed(Key::Up) {
+            velocity.y = -self.speed;
+        }
+        if input.is_pressed(Key::Down) {
+            velocity.y = self.speed;
+        }
+
+        match world.get_mut::<CharacterController>(self.player_entity) {
+            Some(controller) => controller.movement = velocity,
+            None => world.set_physics_velocity(self.player_entity, velocity),
+        }
     }
 
     fn name(&self) -> &'static str {
@@ -6,12 +6,22 @@ pub mod scheduler;
 pub mod query;
 pub mod movement;
 pub mod sync;
+pub mod propagation;
+pub mod parallel;
+pub mod profiler;
+pub mod condition;
+pub mod diagnostics;
 
 // Re-export all systems
-pub use input::InputSystem;
+pub use input::{InputState, InputSystem};
 pub use physics::PhysicsSystem;
 pub use render::RenderSystem;
 pub use movement::{MovementSystem, QueryDemoSystem};
 pub use sync::{VelocitySyncSystem};
-pub use scheduler::{System, Scheduler, QuerySystem, QuerySystemAdapter};
+pub use propagation::TransformSystem;
+pub use scheduler::{System, Scheduler, QuerySystem, QuerySystemAdapter, Stage, SystemConfig, ScheduleError};
+pub use parallel::{ParallelSystem, SystemAccess, WorldView};
+pub use profiler::{Profiler, SystemStats};
+pub use diagnostics::{Diagnostics, DiagnosticsSystem};
+pub use condition::{in_state, not, on_timer, resource_equals, resource_exists, RunCondition};
 pub use query::*;
@@ -1,9 +1,12 @@
+use crate::components::{Entity, PhysicsBody, Position, Sprite, Velocity};
 use crate::world::World;
-use crate::systems::{QuerySystem, Query};
-use crate::systems::query::MovementQuery;
+use crate::systems::QuerySystem;
+use crate::systems::query::{With, Without};
 use crate::query;
 
-/// Movement System - updates positions based on velocities using queries
+/// Movement System - updates positions based on velocities using queries.
+///
+/// Belongs in `Stage::FixedUpdate` so movement does not depend on frame rate.
 pub struct MovementSystem;
 
 impl MovementSystem {
@@ -15,30 +18,11 @@ impl MovementSystem {
 
 impl QuerySystem for MovementSystem {
     fn update_with_queries(&mut self, world: &mut World, dt: f32) {
-        // Example 1: Query for entities with both Position and Velocity
-        // This demonstrates safe, borrow-checked access to multiple components
-        let mut updates = Vec::new();
-        
-        // First pass: collect position updates (immutable borrow)
-        for (entity, position, velocity) in MovementQuery::query(world) {
-            let new_x = position.x + velocity.x * dt;
-            let new_y = position.y + velocity.y * dt;
-            updates.push((entity, new_x, new_y));
-        }
-        
-        // Second pass: apply updates (mutable borrow)
-        for (entity, new_x, new_y) in updates {
-            if let Some(position) = world.positions.get_mut(&entity) {
-                position.x = new_x;
-                position.y = new_y;
-            }
-        }
-        
-        // Example 2: Using the query macro for cleaner syntax
-        // Count how many entities have positions
-        let position_count = query!(world, Position).count();
-        if position_count > 0 {
-            // println!("Updated {} entities with positions", position_count);
+        // Mutate positions while reading velocities in a single, borrow-checked pass.
+        // Rapier integrates entities with a physics body.
+        for (mut position, velocity) in world.query_filtered_mut::<(&mut Position, &Velocity), Without<PhysicsBody>>() {
+            position.x += velocity.x * dt;
+            position.y += velocity.y * dt;
         }
     }
     
@@ -75,8 +59,13 @@ impl QuerySystem for QueryDemoSystem {
             .map(|(entity, _pos, _vel)| entity)
             .collect();
         
-        // 3. Query with filtering (entities that have all three components)
-        let _renderable_count = query!(world, Position, Velocity, Sprite).count();
+        // 3. Query with filters: static entities (position but no velocity)
+        let _static_count = world.query_filtered::<Entity, (With<Position>, Without<Velocity>)>().count();
+
+        // 4. Optional components: every positioned entity, sprite or not
+        let _renderable_count = world.query::<(&Position, Option<&Sprite>)>()
+            .filter(|(_pos, sprite)| sprite.is_some())
+            .count();
         
         // Print statistics (commented out to avoid spam)
         if position_count > 0 {
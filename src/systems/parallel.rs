@@ -0,0 +1,292 @@
+use std::sync::Mutex;
+use rayon::ThreadPool;
+use crate::change_detection::Tick;
+use crate::commands::{CommandQueue, Commands};
+use crate::components::Entity;
+use crate::events::{Event, EventReader, Events};
+use crate::resources::{Resource, Resources};
+use crate::storage::{Component, Components};
+use crate::systems::query::{Access, QueryData, QueryFilter, QueryIter, QuerySource, ReadOnlyQueryData};
+use crate::world::World;
+
+/// Components and resources a parallel system reads and writes.
+///
+/// The `Scheduler` runs systems whose declared sets do not overlap at the
+/// same time; in debug builds, touching anything undeclared panics.
+#[derive(Debug, Default, Clone)]
+pub struct SystemAccess {
+    components: Access,
+    resources: Access,
+    /// Setup run before each parallel batch (e.g. registering event types)
+    inits: Vec<fn(&mut World)>,
+}
+
+impl SystemAccess {
+    /// Declare nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read component `T` (queries with `&T`, `Option<&T>`, `With`, `Changed`, ...)
+    pub fn read<T: Component>(mut self) -> Self {
+        self.components.add_read::<T>();
+        self
+    }
+
+    /// Write component `T` (queries with `&mut T`)
+    pub fn write<T: Component>(mut self) -> Self {
+        self.components.add_write::<T>();
+        self
+    }
+
+    /// Read resource `R`
+    pub fn read_resource<R: Resource>(mut self) -> Self {
+        self.resources.add_read::<R>();
+        self
+    }
+
+    /// Write resource `R`
+    pub fn write_resource<R: Resource>(mut self) -> Self {
+        self.resources.add_write::<R>();
+        self
+    }
+
+    /// Read events of type `E`
+    pub fn read_events<E: Event>(self) -> Self {
+        self.read_resource::<Events<E>>()
+    }
+
+    /// Send events of type `E`
+    pub fn send_events<E: Event>(mut self) -> Self {
+        self.inits.push(|world| world.add_event::<E>());
+        self.write_resource::<Events<E>>()
+    }
+
+    /// Whether two systems with these sets may run at the same time
+    pub fn is_compatible(&self, other: &SystemAccess) -> bool {
+        self.components.is_compatible(&other.components) && self.resources.is_compatible(&other.resources)
+    }
+}
+
+/// A system that declares its data access so it can run on a worker thread
+/// alongside other systems. It sees the world through a `WorldView`;
+/// structural changes go through `WorldView::commands`.
+pub trait ParallelSystem: Send {
+    /// Components and resources the system touches
+    fn access(&self) -> SystemAccess;
+    fn run(&mut self, world: &mut WorldView<'_>, dt: f32);
+    fn name(&self) -> &'static str;
+}
+
+/// World access for a `ParallelSystem`.
+///
+/// Storages and resources the system writes are moved out of the world for
+/// the duration of its run, so concurrent systems only share immutable data.
+pub struct WorldView<'w> {
+    world: &'w World,
+    /// Storages this system writes, lent out of the world
+    components: Components,
+    /// Resources this system writes, lent out of the world
+    resources: Resources,
+    commands: CommandQueue,
+    access: &'w SystemAccess,
+    name: &'static str,
+}
+
+impl<'w> WorldView<'w> {
+    /// Read a component of an entity
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.check::<T>(false);
+        if !self.world.is_alive(entity) {
+            return None;
+        }
+        self.components.get::<T>(entity).or_else(|| self.world.components.get::<T>(entity))
+    }
+
+    /// Mutate a component of an entity, marking it changed
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        self.check::<T>(true);
+        if !self.world.is_alive(entity) {
+            return None;
+        }
+        self.components.get_mut::<T>(entity)
+    }
+
+    /// Run a read-only query, as `World::query`
+    pub fn query<Q: ReadOnlyQueryData>(&self) -> QueryIter<'_, Q> {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// Run a read-only query with filters, as `World::query_filtered`
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&self) -> QueryIter<'_, Q, F> {
+        self.check_query::<Q, F>();
+        let source = QuerySource::layered(&self.components, &self.world.components);
+        // SAFETY: read-only query data never borrows a storage mutably
+        unsafe { QueryIter::new(source, || self.world.entities.iter().collect()) }
+    }
+
+    /// Run a query that may write, as `World::query_mut`
+    pub fn query_mut<Q: QueryData>(&mut self) -> QueryIter<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Run a query with filters that may write, as `World::query_filtered_mut`
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        self.check_query::<Q, F>();
+        let entities = &self.world.entities;
+        let source = QuerySource::layered_mut(&mut self.components, &self.world.components);
+        // SAFETY: writes only reach the storages this view owns, borrowed
+        // exclusively for the iterator's lifetime
+        unsafe { QueryIter::new(source, || entities.iter().collect()) }
+    }
+
+    /// Get a resource
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.check_resource::<R>(false);
+        self.resources.get::<R>().or_else(|| self.world.resource::<R>())
+    }
+
+    /// Get a resource mutably
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.check_resource::<R>(true);
+        self.resources.get_mut::<R>()
+    }
+
+    /// Send an event; declare it with `SystemAccess::send_events`
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        if let Some(events) = self.resource_mut::<Events<E>>() {
+            events.send(event);
+        }
+    }
+
+    /// Read the events of a type that `reader` has not seen yet
+    pub fn read_events<'a, E: Event>(&'a self, reader: &mut EventReader<E>) -> impl Iterator<Item = &'a E> + use<'a, 'w, E> {
+        let events = self.resource::<Events<E>>();
+        let unread = events.map(|events| reader.read(events));
+        unread.into_iter().flatten()
+    }
+
+    /// Record structural changes, applied after the system's batch finishes
+    /// in system order
+    pub fn commands(&self) -> Commands {
+        Commands::new(self.commands.clone(), self.world.entities.reserver())
+    }
+
+    /// Check whether an entity handle still refers to a live entity
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.world.is_alive(entity)
+    }
+
+    fn check<T: Component>(&self, write: bool) {
+        if cfg!(debug_assertions) {
+            let mut used = Access::new();
+            if write { used.add_write::<T>() } else { used.add_read::<T>() }
+            self.check_access(self.access.components.undeclared(&used));
+        }
+    }
+
+    fn check_query<Q: QueryData, F: QueryFilter>(&self) {
+        if cfg!(debug_assertions) {
+            let mut used = Access::new();
+            Q::access(&mut used);
+            F::access(&mut used);
+            self.check_access(self.access.components.undeclared(&used));
+        }
+    }
+
+    fn check_resource<R: Resource>(&self, write: bool) {
+        if cfg!(debug_assertions) {
+            let mut used = Access::new();
+            if write { used.add_write::<R>() } else { used.add_read::<R>() }
+            self.check_access(self.access.resources.undeclared(&used));
+        }
+    }
+
+    fn check_access(&self, undeclared: Option<&'static str>) {
+        if let Some(name) = undeclared {
+            panic!("system {} accesses {} without declaring it in its SystemAccess", self.name, name);
+        }
+    }
+}
+
+/// What a `WorldView` borrowed from the world, to be handed back afterwards
+pub(crate) struct LentData {
+    components: Components,
+    resources: Resources,
+    commands: CommandQueue,
+}
+
+impl LentData {
+    /// Move everything `access` writes out of the world
+    pub(crate) fn take(world: &mut World, access: &SystemAccess, last_run: Tick) -> Self {
+        for init in &access.inits {
+            init(world);
+        }
+        let mut components = Components::with_ticks(world.change_tick(), last_run);
+        for type_id in access.components.writes() {
+            if let Some(storage) = world.components.take_storage(type_id) {
+                components.insert_storage(type_id, storage);
+            }
+        }
+        let mut resources = Resources::new();
+        for type_id in access.resources.writes() {
+            if let Some(resource) = world.resources.take_raw(type_id) {
+                resources.insert_raw(type_id, resource);
+            }
+        }
+        Self { components, resources, commands: CommandQueue::default() }
+    }
+
+    /// Give the lent data back and queue the recorded commands on the world
+    pub(crate) fn restore(self, world: &mut World) {
+        world.components.restore_storages(self.components);
+        world.resources.restore(self.resources);
+        let queued = std::mem::take(&mut *self.commands.lock().unwrap());
+        world.commands().add(move |world| {
+            for command in queued {
+                command(world);
+            }
+        });
+    }
+
+    pub(crate) fn into_view<'w>(self, world: &'w World, access: &'w SystemAccess, name: &'static str) -> WorldView<'w> {
+        WorldView {
+            world,
+            components: self.components,
+            resources: self.resources,
+            commands: self.commands,
+            access,
+            name,
+        }
+    }
+}
+
+impl WorldView<'_> {
+    pub(crate) fn into_lent(self) -> LentData {
+        LentData {
+            components: self.components,
+            resources: self.resources,
+            commands: self.commands,
+        }
+    }
+}
+
+/// Run `jobs` on the workers of `pool`, or one after another on the calling
+/// thread without one, returning the results in job order
+pub(crate) fn run_pool<J: Send, R: Send>(jobs: Vec<J>, pool: Option<&ThreadPool>, run: impl Fn(J) -> R + Sync) -> Vec<R> {
+    let Some(pool) = pool.filter(|_| jobs.len() > 1) else {
+        return jobs.into_iter().map(run).collect();
+    };
+
+    let results: Vec<Mutex<Option<R>>> = jobs.iter().map(|_| Mutex::new(None)).collect();
+    let run = &run;
+    pool.scope(|scope| {
+        for (job, result) in jobs.into_iter().zip(&results) {
+            scope.spawn(move |_| *result.lock().unwrap() = Some(run(job)));
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().expect("the pool runs every job of its scope"))
+        .collect()
+}
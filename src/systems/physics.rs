@@ -1,7 +1,9 @@
 use crate::world::World;
 use crate::systems::System;
 
-/// Physics System - manages Rapier2D physics simulation
+/// Physics System - manages Rapier2D physics simulation.
+///
+/// Belongs in `Stage::FixedUpdate` so every step has the same length.
 pub struct PhysicsSystem;
 
 impl PhysicsSystem {
@@ -12,8 +14,10 @@ impl PhysicsSystem {
 }
 
 impl System for PhysicsSystem {
-    fn update(&mut self, world: &mut World, _dt: f32) {
-        // Step the Rapier2D physics world
+    fn update(&mut self, world: &mut World, dt: f32) {
+        // Step the Rapier2D physics world by the scheduler's step, which is
+        // `FixedTime::timestep` when running in `Stage::FixedUpdate`
+        world.integration_parameters.dt = dt;
         world.step_physics();
     }
 
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+/// Rolling timing statistics for one system (or any other named span),
+/// over the frames kept by the `Profiler`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemStats {
+    pub name: String,
+    /// Frames the span ran in
+    pub samples: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// One timed span, in Chrome trace terms a complete ("X") event
+#[derive(Debug, Clone)]
+struct TraceEvent {
+    name: &'static str,
+    start: Duration,
+    duration: Duration,
+    thread: usize,
+}
+
+/// Spans recorded during one frame
+#[derive(Debug, Default)]
+struct Frame {
+    /// Total time per span name; fixed-step systems may run several times
+    totals: Vec<(&'static str, Duration)>,
+    events: Vec<TraceEvent>,
+}
+
+/// Wall-clock timings of systems over the last `window` frames.
+///
+/// Enabled with `Scheduler::enable_profiling`; code outside the scheduler
+/// (rendering, say) can add spans of its own with `record`.
+pub struct Profiler {
+    window: usize,
+    epoch: Instant,
+    frames: VecDeque<Frame>,
+    current: Frame,
+    /// Small stable ids for the threads seen so far, for trace output
+    threads: HashMap<ThreadId, usize>,
+}
+
+impl Profiler {
+    /// Keep timings of the last `window` frames
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            epoch: Instant::now(),
+            frames: VecDeque::new(),
+            current: Frame::default(),
+            threads: HashMap::new(),
+        }
+    }
+
+    /// Close the current frame and start a new one. Called by the
+    /// `Scheduler` at the start of each update.
+    pub fn begin_frame(&mut self) {
+        let finished = std::mem::take(&mut self.current);
+        if !finished.events.is_empty() {
+            self.frames.push_back(finished);
+        }
+        // The open frame counts towards the window too
+        while self.frames.len() >= self.window {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Record a span that started at `start` and just ended, on the calling thread
+    pub fn record(&mut self, name: &'static str, start: Instant) {
+        let duration = start.elapsed();
+        let thread = std::thread::current().id();
+        self.record_on(name, start, duration, thread);
+    }
+
+    /// Record a span measured on another thread
+    pub(crate) fn record_on(&mut self, name: &'static str, start: Instant, duration: Duration, thread: ThreadId) {
+        let next_id = self.threads.len();
+        let thread = *self.threads.entry(thread).or_insert(next_id);
+        self.current.events.push(TraceEvent {
+            name,
+            start: start.saturating_duration_since(self.epoch),
+            duration,
+            thread,
+        });
+        match self.current.totals.iter_mut().find(|(total_name, _)| *total_name == name) {
+            Some((_, total)) => *total += duration,
+            None => self.current.totals.push((name, duration)),
+        }
+    }
+
+    /// Statistics for every span seen in the window, in first-seen order
+    pub fn stats(&self) -> Vec<SystemStats> {
+        let mut samples: Vec<(&'static str, Vec<Duration>)> = Vec::new();
+        for frame in self.frames.iter().chain(std::iter::once(&self.current)) {
+            for &(name, total) in &frame.totals {
+                match samples.iter_mut().find(|(sample_name, _)| *sample_name == name) {
+                    Some((_, durations)) => durations.push(total),
+                    None => samples.push((name, vec![total])),
+                }
+            }
+        }
+        samples.into_iter().map(|(name, durations)| summarize(name, durations)).collect()
+    }
+
+    /// Statistics for one span, if it was seen in the window
+    pub fn system_stats(&self, name: &str) -> Option<SystemStats> {
+        self.stats().into_iter().find(|stats| stats.name == name)
+    }
+
+    /// Statistics as CSV, one row per span, times in milliseconds
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("system,samples,min_ms,avg_ms,max_ms,p99_ms\n");
+        for stats in self.stats() {
+            let _ = writeln!(
+                csv,
+                "{},{},{:.4},{:.4},{:.4},{:.4}",
+                stats.name, stats.samples, stats.min_ms, stats.avg_ms, stats.max_ms, stats.p99_ms
+            );
+        }
+        csv
+    }
+
+    /// Statistics as a JSON array
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.stats()).expect("profiler stats serialize")
+    }
+
+    /// Every span in the window in Chrome trace event format, for
+    /// `chrome://tracing` or Perfetto
+    pub fn to_chrome_trace(&self) -> String {
+        let events: Vec<serde_json::Value> = self.frames
+            .iter()
+            .chain(std::iter::once(&self.current))
+            .flat_map(|frame| &frame.events)
+            .map(|event| {
+                serde_json::json!({
+                    "name": event.name,
+                    "cat": "system",
+                    "ph": "X",
+                    "ts": event.start.as_secs_f64() * 1e6,
+                    "dur": event.duration.as_secs_f64() * 1e6,
+                    "pid": 1,
+                    "tid": event.thread,
+                })
+            })
+            .collect();
+        serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" }).to_string()
+    }
+
+    /// Write `to_csv` to a file
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_csv())
+    }
+
+    /// Write `to_json` to a file
+    pub fn save_json(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// Write `to_chrome_trace` to a file
+    pub fn save_chrome_trace(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_chrome_trace())
+    }
+}
+
+fn summarize(name: &str, mut durations: Vec<Duration>) -> SystemStats {
+    durations.sort();
+    let millis = |duration: Duration| duration.as_secs_f64() * 1e3;
+    let total: Duration = durations.iter().sum();
+    // Nearest-rank percentile
+    let p99_rank = ((durations.len() as f64 * 0.99).ceil() as usize).clamp(1, durations.len());
+    SystemStats {
+        name: name.to_string(),
+        samples: durations.len(),
+        min_ms: millis(durations[0]),
+        avg_ms: millis(total) / durations.len() as f64,
+        max_ms: millis(durations[durations.len() - 1]),
+        p99_ms: millis(durations[p99_rank - 1]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_stats_and_exports() {
+        let mut profiler = Profiler::new(100);
+        let thread = std::thread::current().id();
+        for frame in 1..=150u64 {
+            profiler.begin_frame();
+            let start = Instant::now();
+            profiler.record_on("Physics", start, Duration::from_millis(frame), thread);
+            // Fixed-step systems add up over a frame
+            profiler.record_on("Movement", start, Duration::from_millis(1), thread);
+            profiler.record_on("Movement", start, Duration::from_millis(1), thread);
+        }
+
+        // Only the last 100 frames (51..=150 ms) are kept
+        let physics = profiler.system_stats("Physics").unwrap();
+        assert_eq!(physics.samples, 100);
+        assert_eq!((physics.min_ms, physics.max_ms, physics.p99_ms), (51.0, 150.0, 149.0));
+        assert!((physics.avg_ms - 100.5).abs() < 1e-9);
+        assert_eq!(profiler.system_stats("Movement").unwrap().max_ms, 2.0);
+
+        assert!(profiler.to_csv().starts_with("system,samples,min_ms,avg_ms,max_ms,p99_ms\nPhysics,100,"));
+        let json: serde_json::Value = serde_json::from_str(&profiler.to_json()).unwrap();
+        assert_eq!(json[1]["name"], "Movement");
+        let trace: serde_json::Value = serde_json::from_str(&profiler.to_chrome_trace()).unwrap();
+        assert_eq!(trace["traceEvents"].as_array().unwrap().len(), 300);
+        assert_eq!(trace["traceEvents"][0]["ph"], "X");
+    }
+}
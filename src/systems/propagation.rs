@@ -0,0 +1,29 @@
+use crate::world::World;
+use crate::systems::System;
+
+/// Transform System - propagates local transforms down the entity hierarchy
+/// so children follow their parents
+pub struct TransformSystem;
+
+impl TransformSystem {
+    /// Create a new transform system
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl System for TransformSystem {
+    fn update(&mut self, world: &mut World, _dt: f32) {
+        world.propagate_transforms();
+    }
+
+    fn name(&self) -> &'static str {
+        "TransformSystem"
+    }
+}
+
+impl Default for TransformSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,230 +1,766 @@
-use crate::components::*;
-use crate::world::World;
-use std::collections::HashMap;
+use std::any::{type_name, TypeId};
+use std::marker::PhantomData;
+use crate::change_detection::{ComponentTicks, Mut, Tick};
+use crate::components::Entity;
+use crate::storage::{Component, ComponentStorage, Components, RawStorage};
 
-/// Query trait for accessing components with borrow checking
-pub trait Query<'world> {
-    type Item;
-    
-    /// Execute the query and return an iterator over matching entities and components
-    fn query(world: &'world World) -> Self::Item;
+/// Types read and written by a query or system, used to reject aliasing
+/// queries such as `(&mut Position, &Position)` before any borrow is handed
+/// out, and to decide which systems may run in parallel.
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+    /// Types whose change ticks are inspected (`Added`/`Changed`); these
+    /// never conflict inside one query but still count as reads elsewhere
+    ticks: Vec<(TypeId, &'static str)>,
 }
 
-/// Mutable query trait for accessing components with mutable borrow checking
-pub trait QueryMut<'world> {
-    type Item;
-    
-    /// Execute the query and return an iterator over matching entities and mutable components
-    fn query_mut(world: &'world mut World) -> Self::Item;
+impl Access {
+    /// Create an empty access set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record read access to a component type
+    pub fn add_read<T: 'static>(&mut self) {
+        self.reads.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    /// Record write access to a component type
+    pub fn add_write<T: 'static>(&mut self) {
+        self.writes.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    /// Record that change ticks of a component type are inspected
+    pub fn add_ticks<T: 'static>(&mut self) {
+        self.ticks.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    /// Types read (including change ticks) but not written
+    pub fn reads(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.reads.iter().chain(&self.ticks).map(|(id, _)| *id)
+    }
+
+    /// Types written
+    pub fn writes(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.writes.iter().map(|(id, _)| *id)
+    }
+
+    /// Whether this and `other` can be used at the same time: neither writes
+    /// anything the other reads or writes
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        let clashes = |a: &Access, b: &Access| {
+            a.writes.iter().any(|(write, _)| b.reads().chain(b.writes()).any(|id| id == *write))
+        };
+        !clashes(self, other) && !clashes(other, self)
+    }
+
+    /// First type `used` touches beyond what this access declares: writes
+    /// must be declared as writes, reads as either
+    pub fn undeclared(&self, used: &Access) -> Option<&'static str> {
+        let written = |id: &TypeId| self.writes.iter().any(|(write, _)| write == id);
+        let read = |id: &TypeId| written(id) || self.reads.iter().any(|(read, _)| read == id);
+        used.writes
+            .iter()
+            .find(|(id, _)| !written(id))
+            .or_else(|| used.reads.iter().chain(&used.ticks).find(|(id, _)| !read(id)))
+            .map(|(_, name)| *name)
+    }
+
+    /// Find a type that is written while also being read or written elsewhere
+    pub fn conflict(&self) -> Option<&'static str> {
+        for (index, (write, name)) in self.writes.iter().enumerate() {
+            let written_twice = self.writes[index + 1..].iter().any(|(other, _)| other == write);
+            let also_read = self.reads.iter().any(|(read, _)| read == write);
+            if written_twice || also_read {
+                return Some(name);
+            }
+        }
+        None
+    }
 }
 
-/// Query iterator for entities with a single component
-pub struct SingleQuery<'world, T> {
-    iter: std::collections::hash_map::Iter<'world, Entity, T>,
+/// Where a query pulls its component storages from.
+///
+/// Holds a raw pointer so several storages can be borrowed mutably at once;
+/// `Access::conflict` guarantees no storage is handed out twice when written.
+/// Parallel systems layer the storages they own over a shared registry.
+pub struct QuerySource<'w> {
+    components: *mut Components,
+    /// Read-only fallback for storages missing from `components`
+    shared: Option<&'w Components>,
+    _marker: PhantomData<&'w mut Components>,
 }
 
-impl<'world, T> Iterator for SingleQuery<'world, T> {
-    type Item = (Entity, &'world T);
-    
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|(&entity, component)| (entity, component))
+impl<'w> QuerySource<'w> {
+    /// Source for read-only queries
+    pub fn new(components: &'w Components) -> Self {
+        Self {
+            components: components as *const Components as *mut Components,
+            shared: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Source for queries that may write
+    pub fn new_mut(components: &'w mut Components) -> Self {
+        Self {
+            components,
+            shared: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like `new`/`new_mut`, reading storages missing from `components`
+    /// from `shared`. Writes only ever reach `components`.
+    pub fn layered(components: &'w Components, shared: &'w Components) -> Self {
+        Self { shared: Some(shared), ..Self::new(components) }
+    }
+
+    /// Writable version of `layered`
+    pub fn layered_mut(components: &'w mut Components, shared: &'w Components) -> Self {
+        Self { shared: Some(shared), ..Self::new_mut(components) }
+    }
+
+    /// Borrow a storage for reading
+    pub fn read<T: Component>(&self) -> Option<&'w ComponentStorage<T>> {
+        // SAFETY: the pointer was created from a reference valid for 'w
+        let own = unsafe { (*self.components).storage::<T>() };
+        own.or_else(|| self.shared?.storage::<T>())
+    }
+
+    /// Tick stamped on components mutated through this query
+    pub fn change_tick(&self) -> Tick {
+        // SAFETY: the pointer was created from a reference valid for 'w
+        unsafe { (*self.components).change_tick() }
+    }
+
+    /// Tick that `Added`/`Changed` filters compare against
+    pub fn last_change_tick(&self) -> Tick {
+        // SAFETY: the pointer was created from a reference valid for 'w
+        unsafe { (*self.components).last_change_tick() }
+    }
+
+    /// Borrow a storage for writing.
+    ///
+    /// # Safety
+    /// The source must have been created with `new_mut`, and the caller must
+    /// not hand out any other borrow of the same storage for 'w.
+    pub unsafe fn write<T: Component>(&self) -> Option<&'w mut ComponentStorage<T>> {
+        unsafe { (*self.components).storage_mut::<T>() }
     }
 }
 
-/// Mutable query iterator for entities with a single component
-pub struct SingleQueryMut<'world, T> {
-    iter: std::collections::hash_map::IterMut<'world, Entity, T>,
+/// Data fetched for each entity matched by a query: `Entity`, `&T`, `&mut T`,
+/// `Option<&T>`, `Option<&mut T>` or a tuple of those.
+///
+/// `&mut T` yields a `Mut<T>`, which marks the component changed when written.
+pub trait QueryData {
+    /// Value yielded for each matching entity
+    type Item<'w>;
+    /// Per-query state holding the borrowed storages
+    type Fetch<'w>;
+
+    /// Record the component types this query reads and writes
+    fn access(access: &mut Access);
+
+    /// Borrow the storages; `None` means a required component was never registered
+    ///
+    /// # Safety
+    /// `access` must have been checked for conflicts on this source.
+    unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>>;
+
+    /// Entities that own a required component, used to drive iteration
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]>;
+
+    /// Whether an entity has everything this query needs
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool;
+
+    /// Fetch the item of a matching entity.
+    ///
+    /// # Safety
+    /// `matches` must have returned true, and each entity may only be fetched
+    /// once per `Fetch` (otherwise mutable items would alias).
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w>;
 }
 
-impl<'world, T> Iterator for SingleQueryMut<'world, T> {
-    type Item = (Entity, &'world mut T);
-    
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|(&entity, component)| (entity, component))
-    }
+/// Marker for query data that never writes, so it can run on `&World`.
+///
+/// # Safety
+/// Implementors must not call `QuerySource::write`.
+pub unsafe trait ReadOnlyQueryData: QueryData {}
+
+/// Filters that narrow a query without fetching data: `With<T>`, `Without<T>`,
+/// `Added<T>`, `Changed<T>` or a tuple of filters (all must match).
+pub trait QueryFilter {
+    type Fetch<'w>;
+
+    fn access(access: &mut Access);
+    fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>>;
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]>;
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool;
 }
 
-/// Query for entities with Position component
-pub struct PositionQuery;
+/// Only match entities that have component `T`
+pub struct With<T>(PhantomData<T>);
+
+/// Only match entities that do not have component `T`
+pub struct Without<T>(PhantomData<T>);
+
+/// Only match entities whose `T` was added since the running system last ran
+pub struct Added<T>(PhantomData<T>);
+
+/// Only match entities whose `T` was added or mutated since the running system last ran
+pub struct Changed<T>(PhantomData<T>);
+
+/// Mutable borrow of one storage, kept as raw pointers so items can point
+/// into the component data while entities are still being looked up.
+pub struct WriteFetch<'w, T> {
+    storage: RawStorage<'w, T>,
+    change_tick: Tick,
+}
 
-impl<'world> Query<'world> for PositionQuery {
-    type Item = SingleQuery<'world, Position>;
-    
-    fn query(world: &'world World) -> Self::Item {
-        SingleQuery {
-            iter: world.positions.iter(),
+impl<'w, T: Component> WriteFetch<'w, T> {
+    fn new(storage: &'w mut ComponentStorage<T>, change_tick: Tick) -> Self {
+        Self {
+            storage: storage.raw_mut(),
+            change_tick,
         }
     }
+
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        self.storage.dense_index(entity)
+    }
+
+    /// # Safety
+    /// No other live reference to this entity's component may exist.
+    unsafe fn get(&self, entity: Entity) -> Option<Mut<'w, T>> {
+        let dense = self.dense_index(entity)?;
+        // SAFETY: dense is in bounds, and the caller guarantees uniqueness
+        let (value, ticks) = unsafe { self.storage.get_mut(dense) };
+        Some(Mut::new(value, &mut ticks.changed, self.change_tick))
+    }
 }
 
-impl<'world> QueryMut<'world> for PositionQuery {
-    type Item = SingleQueryMut<'world, Position>;
-    
-    fn query_mut(world: &'world mut World) -> Self::Item {
-        SingleQueryMut {
-            iter: world.positions.iter_mut(),
-        }
+impl QueryData for Entity {
+    type Item<'w> = Entity;
+    type Fetch<'w> = ();
+
+    fn access(_access: &mut Access) {}
+
+    unsafe fn init_fetch<'w>(_source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        Some(())
+    }
+
+    fn driver<'w>(_fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        None
+    }
+
+    fn matches(_fetch: &Self::Fetch<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(_fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+        entity
     }
 }
 
-/// Query for entities with Velocity component
-pub struct VelocityQuery;
+unsafe impl ReadOnlyQueryData for Entity {}
 
-impl<'world> Query<'world> for VelocityQuery {
-    type Item = SingleQuery<'world, Velocity>;
-    
-    fn query(world: &'world World) -> Self::Item {
-        SingleQuery {
-            iter: world.velocities.iter(),
-        }
+impl<T: Component> QueryData for &T {
+    type Item<'w> = &'w T;
+    type Fetch<'w> = &'w ComponentStorage<T>;
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        source.read::<T>()
+    }
+
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        Some(fetch.entities())
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        fetch.contains(entity)
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+        let storage: &'w ComponentStorage<T> = fetch;
+        storage.get(entity).expect("query fetched an entity that does not match")
     }
 }
 
-impl<'world> QueryMut<'world> for VelocityQuery {
-    type Item = SingleQueryMut<'world, Velocity>;
-    
-    fn query_mut(world: &'world mut World) -> Self::Item {
-        SingleQueryMut {
-            iter: world.velocities.iter_mut(),
-        }
+unsafe impl<T: Component> ReadOnlyQueryData for &T {}
+
+impl<T: Component> QueryData for &mut T {
+    type Item<'w> = Mut<'w, T>;
+    type Fetch<'w> = WriteFetch<'w, T>;
+
+    fn access(access: &mut Access) {
+        access.add_write::<T>();
+    }
+
+    unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        let change_tick = source.change_tick();
+        unsafe { source.write::<T>() }.map(|storage| WriteFetch::new(storage, change_tick))
+    }
+
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        Some(fetch.storage.entities())
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        fetch.dense_index(entity).is_some()
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+        unsafe { fetch.get(entity) }.expect("query fetched an entity that does not match")
     }
 }
 
-/// Query for entities with Sprite component
-pub struct SpriteQuery;
+impl<T: Component> QueryData for Option<&T> {
+    type Item<'w> = Option<&'w T>;
+    type Fetch<'w> = Option<&'w ComponentStorage<T>>;
 
-impl<'world> Query<'world> for SpriteQuery {
-    type Item = SingleQuery<'world, Sprite>;
-    
-    fn query(world: &'world World) -> Self::Item {
-        SingleQuery {
-            iter: world.sprites.iter(),
-        }
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        Some(source.read::<T>())
+    }
+
+    fn driver<'w>(_fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        None
+    }
+
+    fn matches(_fetch: &Self::Fetch<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+        fetch.and_then(|storage| storage.get(entity))
     }
 }
 
-impl<'world> QueryMut<'world> for SpriteQuery {
-    type Item = SingleQueryMut<'world, Sprite>;
-    
-    fn query_mut(world: &'world mut World) -> Self::Item {
-        SingleQueryMut {
-            iter: world.sprites.iter_mut(),
-        }
+unsafe impl<T: Component> ReadOnlyQueryData for Option<&T> {}
+
+impl<T: Component> QueryData for Option<&mut T> {
+    type Item<'w> = Option<Mut<'w, T>>;
+    type Fetch<'w> = Option<WriteFetch<'w, T>>;
+
+    fn access(access: &mut Access) {
+        access.add_write::<T>();
+    }
+
+    unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        let change_tick = source.change_tick();
+        Some(unsafe { source.write::<T>() }.map(|storage| WriteFetch::new(storage, change_tick)))
+    }
+
+    fn driver<'w>(_fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        None
+    }
+
+    fn matches(_fetch: &Self::Fetch<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+        fetch.as_ref().and_then(|storage| unsafe { storage.get(entity) })
     }
 }
 
-/// Query iterator for entities with two components (Position + Velocity)
-pub struct PositionVelocityQuery<'world> {
-    positions: &'world HashMap<Entity, Position>,
-    velocities: &'world HashMap<Entity, Velocity>,
-    entity_iter: std::collections::hash_map::Keys<'world, Entity, Position>,
+impl<T: Component> QueryFilter for With<T> {
+    type Fetch<'w> = &'w ComponentStorage<T>;
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        source.read::<T>()
+    }
+
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        Some(fetch.entities())
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        fetch.contains(entity)
+    }
 }
 
-impl<'world> Iterator for PositionVelocityQuery<'world> {
-    type Item = (Entity, &'world Position, &'world Velocity);
-    
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(&entity) = self.entity_iter.next() {
-            if let (Some(position), Some(velocity)) = (
-                self.positions.get(&entity),
-                self.velocities.get(&entity)
-            ) {
-                return Some((entity, position, velocity));
-            }
-        }
+impl<T: Component> QueryFilter for Without<T> {
+    type Fetch<'w> = Option<&'w ComponentStorage<T>>;
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        Some(source.read::<T>())
+    }
+
+    fn driver<'w>(_fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
         None
     }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        !fetch.is_some_and(|storage| storage.contains(entity))
+    }
 }
 
-// Note: Mutable queries for multiple components require more sophisticated 
-// lifetime management to ensure safe borrow checking. For now, we provide
-// separate mutable access to individual component types.
+/// Tick lookup for `Added`/`Changed`. Shares the raw view `WriteFetch` uses
+/// so it can run next to a `&mut T` fetch of the same storage.
+pub struct TicksFetch<'w, T> {
+    storage: RawStorage<'w, T>,
+    last_run: Tick,
+}
 
-/// Query for entities with both Position and Velocity components
-pub struct MovementQuery;
+impl<'w, T: Component> TicksFetch<'w, T> {
+    fn new(source: &QuerySource<'w>) -> Option<Self> {
+        Some(Self {
+            storage: source.read::<T>()?.raw(),
+            last_run: source.last_change_tick(),
+        })
+    }
 
-impl<'world> Query<'world> for MovementQuery {
-    type Item = PositionVelocityQuery<'world>;
-    
-    fn query(world: &'world World) -> Self::Item {
-        PositionVelocityQuery {
-            positions: &world.positions,
-            velocities: &world.velocities,
-            entity_iter: world.positions.keys(),
-        }
+    fn get(&self, entity: Entity) -> Option<ComponentTicks> {
+        let dense = self.storage.dense_index(entity)?;
+        Some(self.storage.ticks(dense))
     }
 }
 
-/// Query for entities with Position, Velocity, and Sprite components
-pub struct RenderableMovementQuery<'world> {
-    positions: &'world HashMap<Entity, Position>,
-    velocities: &'world HashMap<Entity, Velocity>,
-    sprites: &'world HashMap<Entity, Sprite>,
-    entity_iter: std::collections::hash_map::Keys<'world, Entity, Position>,
+impl<T: Component> QueryFilter for Added<T> {
+    type Fetch<'w> = TicksFetch<'w, T>;
+
+    fn access(access: &mut Access) {
+        access.add_ticks::<T>();
+    }
+
+    fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        TicksFetch::new(source)
+    }
+
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        Some(fetch.storage.entities())
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        fetch.get(entity).is_some_and(|ticks| ticks.is_added(fetch.last_run))
+    }
 }
 
-impl<'world> Iterator for RenderableMovementQuery<'world> {
-    type Item = (Entity, &'world Position, &'world Velocity, &'world Sprite);
-    
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(&entity) = self.entity_iter.next() {
-            if let (Some(position), Some(velocity), Some(sprite)) = (
-                self.positions.get(&entity),
-                self.velocities.get(&entity),
-                self.sprites.get(&entity)
-            ) {
-                return Some((entity, position, velocity, sprite));
+impl<T: Component> QueryFilter for Changed<T> {
+    type Fetch<'w> = TicksFetch<'w, T>;
+
+    fn access(access: &mut Access) {
+        access.add_ticks::<T>();
+    }
+
+    fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+        TicksFetch::new(source)
+    }
+
+    fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+        Some(fetch.storage.entities())
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+        fetch.get(entity).is_some_and(|ticks| ticks.is_changed(fetch.last_run))
+    }
+}
+
+/// Pick the shorter of two candidate driver lists
+fn shortest<'w>(a: Option<&'w [Entity]>, b: Option<&'w [Entity]>) -> Option<&'w [Entity]> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.len() < a.len() { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        impl<$($name: QueryData),*> QueryData for ($($name,)*) {
+            type Item<'w> = ($($name::Item<'w>,)*);
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            unsafe fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+                Some(($(unsafe { $name::init_fetch(source) }?,)*))
+            }
+
+            fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+                let ($($name,)*) = fetch;
+                let driver = None;
+                $(let driver = shortest(driver, $name::driver($name));)*
+                driver
+            }
+
+            fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+                let ($($name,)*) = fetch;
+                true $(&& $name::matches($name, entity))*
+            }
+
+            unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, entity: Entity) -> Self::Item<'w> {
+                let ($($name,)*) = fetch;
+                ($(unsafe { $name::fetch($name, entity) },)*)
             }
         }
-        None
+
+        unsafe impl<$($name: ReadOnlyQueryData),*> ReadOnlyQueryData for ($($name,)*) {}
+
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        impl<$($name: QueryFilter),*> QueryFilter for ($($name,)*) {
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn init_fetch<'w>(source: &QuerySource<'w>) -> Option<Self::Fetch<'w>> {
+                Some(($($name::init_fetch(source)?,)*))
+            }
+
+            fn driver<'w>(fetch: &Self::Fetch<'w>) -> Option<&'w [Entity]> {
+                let ($($name,)*) = fetch;
+                let driver = None;
+                $(let driver = shortest(driver, $name::driver($name));)*
+                driver
+            }
+
+            fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool {
+                let ($($name,)*) = fetch;
+                true $(&& $name::matches($name, entity))*
+            }
+        }
+    };
+}
+
+impl_query_tuple!();
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+impl_query_tuple!(A, B, C, D, E, F, G);
+impl_query_tuple!(A, B, C, D, E, F, G, H);
+
+/// Entities a query walks over
+enum Candidates<'w> {
+    /// Dense entity list of the smallest required storage
+    Dense(std::slice::Iter<'w, Entity>),
+    /// Every live entity (no required component in the query)
+    Alive(std::vec::IntoIter<Entity>),
+    /// A required storage does not exist, nothing can match
+    Empty,
+}
+
+/// Iterator over the entities matched by a query
+pub struct QueryIter<'w, Q: QueryData, F: QueryFilter = ()> {
+    candidates: Candidates<'w>,
+    data: Option<Q::Fetch<'w>>,
+    filter: Option<F::Fetch<'w>>,
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> QueryIter<'w, Q, F> {
+    /// Build a query over a component registry.
+    ///
+    /// # Safety
+    /// If `Q` is not `ReadOnlyQueryData`, `source` must have been created with
+    /// `QuerySource::new_mut` and nothing else may borrow the storages for 'w.
+    pub unsafe fn new(source: QuerySource<'w>, alive: impl FnOnce() -> Vec<Entity>) -> Self {
+        let mut access = Access::new();
+        Q::access(&mut access);
+        F::access(&mut access);
+        if let Some(component) = access.conflict() {
+            panic!(
+                "query {} accesses {} mutably more than once",
+                type_name::<(Q, F)>(),
+                component
+            );
+        }
+
+        let data = unsafe { Q::init_fetch(&source) };
+        let filter = F::init_fetch(&source);
+        let candidates = match (&data, &filter) {
+            (Some(data), Some(filter)) => {
+                match shortest(Q::driver(data), F::driver(filter)) {
+                    Some(entities) => Candidates::Dense(entities.iter()),
+                    None => Candidates::Alive(alive().into_iter()),
+                }
+            }
+            _ => Candidates::Empty,
+        };
+
+        Self { candidates, data, filter }
     }
 }
 
-/// Query for entities with Position, Velocity, and Sprite components
-pub struct RenderableQuery;
+impl<'w, Q: QueryData, F: QueryFilter> Iterator for QueryIter<'w, Q, F> {
+    type Item = Q::Item<'w>;
 
-impl<'world> Query<'world> for RenderableQuery {
-    type Item = RenderableMovementQuery<'world>;
-    
-    fn query(world: &'world World) -> Self::Item {
-        RenderableMovementQuery {
-            positions: &world.positions,
-            velocities: &world.velocities,
-            sprites: &world.sprites,
-            entity_iter: world.positions.keys(),
+    fn next(&mut self) -> Option<Self::Item> {
+        let (data, filter) = (self.data.as_mut()?, self.filter.as_ref()?);
+        loop {
+            let entity = match &mut self.candidates {
+                Candidates::Dense(entities) => *entities.next()?,
+                Candidates::Alive(entities) => entities.next()?,
+                Candidates::Empty => return None,
+            };
+
+            if Q::matches(data, entity) && F::matches(filter, entity) {
+                // SAFETY: candidate lists never repeat an entity
+                return Some(unsafe { Q::fetch(data, entity) });
+            }
         }
     }
 }
 
-/// Helper macros for common query patterns
+/// Helper macro for read-only queries: yields `(entity, &A, &B, ...)`
 #[macro_export]
 macro_rules! query {
-    ($world:expr, Position) => {
-        $crate::systems::query::PositionQuery::query($world)
-    };
-    ($world:expr, Velocity) => {
-        $crate::systems::query::VelocityQuery::query($world)
-    };
-    ($world:expr, Sprite) => {
-        $crate::systems::query::SpriteQuery::query($world)
-    };
-    ($world:expr, Position, Velocity) => {
-        $crate::systems::query::MovementQuery::query($world)
-    };
-    ($world:expr, Position, Velocity, Sprite) => {
-        $crate::systems::query::RenderableQuery::query($world)
+    ($world:expr, $($component:ty),+ $(,)?) => {
+        $world.query::<($crate::components::Entity, $(&$component,)+)>()
     };
 }
 
+/// Helper macro for mutable queries: yields `(entity, Mut<A>, Mut<B>, ...)`.
+/// Use `world.query_mut::<(...)>()` directly to mix `&mut` and `&` access.
 #[macro_export]
 macro_rules! query_mut {
-    ($world:expr, Position) => {
-        $crate::systems::query::PositionQuery::query_mut($world)
-    };
-    ($world:expr, Velocity) => {
-        $crate::systems::query::VelocityQuery::query_mut($world)
-    };
-    ($world:expr, Sprite) => {
-        $crate::systems::query::SpriteQuery::query_mut($world)
+    ($world:expr, $($component:ty),+ $(,)?) => {
+        $world.query_mut::<($crate::components::Entity, $(&mut $component,)+)>()
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::{Position, Sprite, Velocity};
+    use crate::world::World;
+    use super::*;
+
+    fn spawn(world: &mut World, x: f32, velocity: Option<f32>) -> Entity {
+        let entity = world.create_entity();
+        world.add_position(entity, Position::new(x, 0.0));
+        if let Some(vx) = velocity {
+            world.add_velocity(entity, Velocity::new(vx, 0.0));
+        }
+        entity
+    }
+
+    #[test]
+    fn test_mutable_query_updates_in_one_pass() {
+        let mut world = World::new();
+        let moving = spawn(&mut world, 0.0, Some(10.0));
+        let still = spawn(&mut world, 5.0, None);
+
+        for (mut position, velocity) in world.query_mut::<(&mut Position, &Velocity)>() {
+            position.x += velocity.x;
+        }
+
+        assert_eq!(world.get::<Position>(moving).map(|p| p.x), Some(10.0));
+        assert_eq!(world.get::<Position>(still).map(|p| p.x), Some(5.0));
+    }
+
+    #[test]
+    fn test_filters_and_optional_components() {
+        let mut world = World::new();
+        let moving = spawn(&mut world, 0.0, Some(1.0));
+        let still = spawn(&mut world, 1.0, None);
+
+        let without: Vec<Entity> = world
+            .query_filtered::<Entity, (With<Position>, Without<Velocity>)>()
+            .collect();
+        assert_eq!(without, vec![still]);
+
+        let with: Vec<Entity> = world.query_filtered::<Entity, With<Velocity>>().collect();
+        assert_eq!(with, vec![moving]);
+
+        let optional: Vec<(Entity, bool)> = world
+            .query::<(Entity, &Position, Option<&Velocity>)>()
+            .map(|(entity, _, velocity)| (entity, velocity.is_some()))
+            .collect();
+        assert_eq!(optional, vec![(moving, true), (still, false)]);
+
+        // A required component nobody ever inserted matches nothing
+        assert_eq!(world.query::<(&Position, &Sprite)>().count(), 0);
+    }
+
+    #[test]
+    fn test_change_filters_compare_against_last_run() {
+        let mut world = World::new();
+        let moving = spawn(&mut world, 0.0, Some(1.0));
+        let still = spawn(&mut world, 0.0, None);
+
+        // Everything inserted before the first run counts as added
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().count(), 2);
+
+        let last_run = world.change_tick();
+        world.increment_change_tick();
+        world.set_last_change_tick(last_run);
+        assert_eq!(world.query_filtered::<Entity, Changed<Position>>().count(), 0);
+
+        // Reading through &mut does not mark; writing does
+        for (mut position, velocity) in world.query_mut::<(&mut Position, &Velocity)>() {
+            if velocity.x > 0.0 {
+                position.x += velocity.x;
+            }
+        }
+        for (_entity, position) in query_mut!(world, Position) {
+            let _ = position.x;
+        }
+        let changed: Vec<Entity> = world
+            .query_filtered_mut::<Entity, Changed<Position>>()
+            .collect();
+        assert_eq!(changed, vec![moving]);
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().count(), 0);
+
+        world.remove::<Position>(still);
+        let mut removed = crate::change_detection::RemovedComponents::<Position>::new();
+        assert_eq!(removed.read(&world).collect::<Vec<_>>(), vec![still]);
+        assert_eq!(removed.read(&world).count(), 0);
+    }
+
+    #[test]
+    fn test_mutable_fetch_with_change_filter_on_same_component() {
+        let mut world = World::new();
+        let moved = spawn(&mut world, 0.0, None);
+        let still = spawn(&mut world, 0.0, None);
+
+        let last_run = world.change_tick();
+        world.increment_change_tick();
+        world.set_last_change_tick(last_run);
+        world.get_mut::<Position>(moved).unwrap().x = 1.0;
+
+        let mut seen = Vec::new();
+        for (entity, mut position) in world.query_filtered_mut::<(Entity, &mut Position), Changed<Position>>() {
+            position.x += 1.0;
+            seen.push(entity);
+        }
+        assert_eq!(seen, vec![moved]);
+        assert_eq!(world.get::<Position>(moved).map(|p| p.x), Some(2.0));
+        assert_eq!(world.get::<Position>(still).map(|p| p.x), Some(0.0));
+
+        // Nothing was inserted since the last run
+        assert_eq!(world.query_filtered_mut::<&mut Position, Added<Position>>().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "mutably more than once")]
+    fn test_aliasing_query_panics() {
+        let mut world = World::new();
+        spawn(&mut world, 0.0, None);
+        let _ = world.query_mut::<(&mut Position, &Position)>();
+    }
+}
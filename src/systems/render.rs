@@ -1,11 +1,9 @@
-use crate::components::{Sprite, TextureSprite};
+use crate::components::{PhysicsInterpolation, Position, Sprite, TextureSprite};
 use crate::components::texture::SpriteAtlas;
+use crate::resources::{FixedTime, ScreenSize};
 use crate::world::World;
 use crate::systems::System;
 
-const WIDTH: usize = 800;
-const HEIGHT: usize = 600;
-
 /// Render System - handles drawing sprites and textures
 pub struct RenderSystem;
 
@@ -15,38 +13,51 @@ impl RenderSystem {
         Self
     }
 
-    /// Render a frame to the buffer
-    pub fn render_frame(buffer: &mut Vec<u32>, world: &World) {
+    /// Render a frame to the buffer, which must hold `ScreenSize::pixel_count` pixels
+    pub fn render_frame(buffer: &mut [u32], world: &World) {
+        let screen = world.resource::<ScreenSize>().copied().unwrap_or_default();
+
         // Clear the screen with black
         for pixel in buffer.iter_mut() {
             *pixel = 0xFF000000; // Black with full alpha
         }
 
+        // Physics bodies are drawn between their last two fixed steps
+        let alpha = world.resource::<FixedTime>().map_or(1.0, FixedTime::alpha);
+
         // Render all entities with positions
-        for (entity, position) in &world.positions {
+        for (entity, position) in world.iter::<Position>() {
+            let (x, y) = match world.get::<PhysicsInterpolation>(entity) {
+                Some(interpolation) => {
+                    let translation = interpolation.translation(alpha);
+                    (translation.x, translation.y)
+                }
+                None => (position.x, position.y),
+            };
+
             // Check for texture sprite first, then regular sprite
-            if let Some(texture_sprite) = world.get_texture_sprite(*entity) {
+            if let Some(texture_sprite) = world.get_texture_sprite(entity) {
                 if let Some(atlas) = &world.sprite_atlas {
-                    Self::draw_texture_sprite(buffer, position.x, position.y, texture_sprite, atlas);
+                    Self::draw_texture_sprite(buffer, screen, x, y, texture_sprite, atlas);
                 }
-            } else if let Some(sprite) = world.get_sprite(*entity) {
-                Self::draw_sprite(buffer, position.x, position.y, sprite);
+            } else if let Some(sprite) = world.get_sprite(entity) {
+                Self::draw_sprite(buffer, screen, x, y, sprite);
             } else {
                 // Fallback: draw a default red square if no sprite
-                Self::draw_default_square(buffer, position.x, position.y);
+                Self::draw_default_square(buffer, screen, x, y);
             }
         }
     }
 
     /// Draw a basic sprite
-    fn draw_sprite(buffer: &mut Vec<u32>, x: f32, y: f32, sprite: &Sprite) {
+    fn draw_sprite(buffer: &mut [u32], screen: ScreenSize, x: f32, y: f32, sprite: &Sprite) {
         let sprite_x = x as usize;
         let sprite_y = y as usize;
 
         for y in sprite_y..sprite_y + sprite.size {
             for x in sprite_x..sprite_x + sprite.size {
-                if x < WIDTH && y < HEIGHT {
-                    let index = y * WIDTH + x;
+                if x < screen.width && y < screen.height {
+                    let index = y * screen.width + x;
                     buffer[index] = sprite.color | 0xFF000000; // Ensure alpha is set
                 }
             }
@@ -54,7 +65,7 @@ impl RenderSystem {
     }
 
     /// Draw a texture sprite from an atlas
-    fn draw_texture_sprite(buffer: &mut Vec<u32>, x: f32, y: f32, texture_sprite: &TextureSprite, atlas: &SpriteAtlas) {
+    fn draw_texture_sprite(buffer: &mut [u32], screen: ScreenSize, x: f32, y: f32, texture_sprite: &TextureSprite, atlas: &SpriteAtlas) {
         if let Some(atlas_sprite) = atlas.get_sprite(&texture_sprite.atlas_name) {
             let dest_x = x as i32;
             let dest_y = y as i32;
@@ -66,7 +77,7 @@ impl RenderSystem {
                     let screen_x = dest_x + dx;
                     let screen_y = dest_y + dy;
                     
-                    if screen_x >= 0 && screen_x < WIDTH as i32 && screen_y >= 0 && screen_y < HEIGHT as i32 {
+                    if screen_x >= 0 && screen_x < screen.width as i32 && screen_y >= 0 && screen_y < screen.height as i32 {
                         // Map screen coordinates back to atlas coordinates
                         let atlas_x = atlas_sprite.x + (dx as f32 / texture_sprite.scale) as usize;
                         let atlas_y = atlas_sprite.y + (dy as f32 / texture_sprite.scale) as usize;
@@ -75,7 +86,7 @@ impl RenderSystem {
                         
                         // Only draw non-transparent pixels
                         if (pixel >> 24) & 0xFF > 0 {
-                            let index = screen_y as usize * WIDTH + screen_x as usize;
+                            let index = screen_y as usize * screen.width + screen_x as usize;
                             buffer[index] = pixel;
                         }
                     }
@@ -85,7 +96,7 @@ impl RenderSystem {
     }
 
     /// Draw a default square for entities without sprites
-    fn draw_default_square(buffer: &mut Vec<u32>, x: f32, y: f32) {
+    fn draw_default_square(buffer: &mut [u32], screen: ScreenSize, x: f32, y: f32) {
         let sprite_x = x as usize;
         let sprite_y = y as usize;
         let default_size = 20;
@@ -93,8 +104,8 @@ impl RenderSystem {
 
         for y in sprite_y..sprite_y + default_size {
             for x in sprite_x..sprite_x + default_size {
-                if x < WIDTH && y < HEIGHT {
-                    let index = y * WIDTH + x;
+                if x < screen.width && y < screen.height {
+                    let index = y * screen.width + x;
                     buffer[index] = default_color;
                 }
             }
@@ -1,3 +1,14 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use crate::change_detection::Tick;
+use crate::resources::{FixedTime, Time};
+use crate::state::{State, StateDriver, StateHook};
+use crate::systems::condition::{in_state, RunCondition};
+use crate::systems::parallel::{run_pool, LentData, ParallelSystem, SystemAccess};
+use crate::systems::profiler::Profiler;
 use crate::world::World;
 
 /// System trait that all systems must implement
@@ -34,34 +45,622 @@ impl<T: QuerySystem> System for QuerySystemAdapter<T> {
     }
 }
 
-/// ECS Scheduler for managing update systems
+/// Phases of a frame. Every system runs in one stage and stages run in
+/// declaration order, so e.g. all `FixedUpdate` systems finish before any
+/// `Update` system starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Stage {
+    /// Input capture and other per-frame setup
+    PreUpdate,
+    /// Physics and the simulation it drives
+    FixedUpdate,
+    /// Gameplay logic
+    #[default]
+    Update,
+    /// Hierarchy propagation and other cleanup after gameplay
+    PostUpdate,
+    /// Preparing the frame for drawing
+    Render,
+}
+
+impl Stage {
+    /// Every stage, in execution order
+    pub const ALL: [Stage; 5] = [
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::Render,
+    ];
+}
+
+/// Why the scheduler could not resolve a system order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// `before`/`after` constraints form a loop; `systems` lists it in order
+    Cycle { stage: Stage, systems: Vec<&'static str> },
+    /// A constraint names a label no system carries
+    UnknownLabel { system: &'static str, label: &'static str },
+    /// A constraint asks for an order that contradicts the stage order
+    StageConflict { system: &'static str, label: &'static str },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Cycle { stage, systems } => {
+                write!(f, "system order cycle in {:?}: {}", stage, systems.join(" -> "))?;
+                if let Some(first) = systems.first() {
+                    write!(f, " -> {}", first)?;
+                }
+                Ok(())
+            }
+            ScheduleError::UnknownLabel { system, label } => {
+                write!(f, "system {} is ordered against unknown label '{}'", system, label)
+            }
+            ScheduleError::StageConflict { system, label } => {
+                write!(f, "system {} is ordered against '{}' in a way its stage contradicts", system, label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// How a system gets at the world
+enum SystemKind {
+    /// Runs alone with `&mut World`
+    Exclusive(Box<dyn System>),
+    /// Runs on a worker thread next to systems it does not conflict with
+    Parallel(Box<dyn ParallelSystem>, SystemAccess),
+}
+
+/// A system plus its ordering constraints and the change tick of its previous run
+struct SystemEntry {
+    system: SystemKind,
+    stage: Stage,
+    /// Extra labels besides the system name
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    /// All must hold for the system to run
+    conditions: Vec<RunCondition>,
+    /// Cleared by `Scheduler::disable_system`
+    enabled: bool,
+    /// Set for `on_enter`/`on_exit` systems, which only run on transitions
+    hook: Option<StateHook>,
+    last_run: Tick,
+}
+
+impl SystemEntry {
+    fn new(system: SystemKind) -> Self {
+        Self {
+            system,
+            stage: Stage::default(),
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+            enabled: true,
+            hook: None,
+            last_run: 0,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match &self.system {
+            SystemKind::Exclusive(system) => system.name(),
+            SystemKind::Parallel(system, _) => system.name(),
+        }
+    }
+
+    fn has_label(&self, label: &str) -> bool {
+        self.name() == label || self.labels.contains(&label)
+    }
+
+    /// Whether the system is enabled and its run conditions hold. Every
+    /// condition is checked, so stateful ones like `on_timer` keep time.
+    fn should_run(&mut self, world: &World) -> bool {
+        let mut run = self.enabled;
+        for condition in &mut self.conditions {
+            run &= condition(world);
+        }
+        run
+    }
+
+    /// Whether this system may run at the same time as `other`
+    fn is_compatible(&self, other: &SystemEntry) -> bool {
+        match (&self.system, &other.system) {
+            (SystemKind::Parallel(_, a), SystemKind::Parallel(_, b)) => a.is_compatible(b),
+            _ => false,
+        }
+    }
+}
+
+/// Stage and ordering options for a system just added to a `Scheduler`.
+///
+/// A system is labelled with its `name()` and any extra labels;
+/// `before`/`after` accept either.
+pub struct SystemConfig<'a> {
+    entry: &'a mut SystemEntry,
+    /// The scheduler's state machines, extended by state-dependent systems
+    states: &'a mut Vec<StateDriver>,
+}
+
+impl SystemConfig<'_> {
+    /// Run the system in `stage` instead of `Stage::Update`
+    pub fn in_stage(self, stage: Stage) -> Self {
+        self.entry.stage = stage;
+        self
+    }
+
+    /// Add a label other systems can order against
+    pub fn label(self, label: &'static str) -> Self {
+        self.entry.labels.push(label);
+        self
+    }
+
+    /// Run before every system carrying `label`
+    pub fn before(self, label: &'static str) -> Self {
+        self.entry.before.push(label);
+        self
+    }
+
+    /// Run after every system carrying `label`
+    pub fn after(self, label: &'static str) -> Self {
+        self.entry.after.push(label);
+        self
+    }
+
+    /// Only run while `condition` returns true; see `systems::condition` for
+    /// common ones. With several conditions, all must hold.
+    ///
+    /// A skipped system keeps its place in the order, and its change
+    /// detection covers everything since it last actually ran.
+    pub fn run_if(self, condition: impl FnMut(&World) -> bool + 'static) -> Self {
+        self.entry.conditions.push(Box::new(condition));
+        self
+    }
+
+    /// Run once each time state machine `S` enters `state`, instead of every frame
+    pub fn on_enter<S: State>(self, state: S) -> Self {
+        self.entry.hook = Some(StateHook::new(state, true));
+        self.track_state::<S>()
+    }
+
+    /// Run once each time state machine `S` leaves `state`, instead of every frame
+    pub fn on_exit<S: State>(self, state: S) -> Self {
+        self.entry.hook = Some(StateHook::new(state, false));
+        self.track_state::<S>()
+    }
+
+    /// Run in the system's stage only while state machine `S` is in `state`
+    pub fn on_update<S: State>(self, state: S) -> Self {
+        self.track_state::<S>().run_if(in_state(state))
+    }
+
+    fn track_state<S: State>(self) -> Self {
+        add_state_driver::<S>(self.states);
+        self
+    }
+}
+
+/// Systems of one stage that run at the same time, as indices into `Scheduler::systems`
+type Batch = Vec<usize>;
+
+/// ECS Scheduler for managing update systems.
+///
+/// Systems run stage by stage; inside a stage, `before`/`after` constraints
+/// decide the order and insertion order breaks ties. Parallel systems whose
+/// declared access does not conflict are grouped into batches that run
+/// concurrently on a pool of worker threads.
 pub struct Scheduler {
-    systems: Vec<Box<dyn System>>,
+    systems: Vec<SystemEntry>,
+    /// Resolved batches in execution order, tagged with their stage
+    batches: Vec<(Stage, Batch)>,
+    /// Whether `systems` needs re-sorting before the next run
+    dirty: bool,
+    /// Worker threads for parallel batches
+    thread_count: usize,
+    /// Workers running parallel batches, started with the first one and
+    /// kept for the scheduler's lifetime
+    pool: Option<ThreadPool>,
+    /// Per-system timings, when profiling is enabled
+    profiler: Option<Profiler>,
+    /// State machines whose transitions the scheduler applies
+    states: Vec<StateDriver>,
 }
 
 impl Scheduler {
-    /// Create a new scheduler
+    /// Create a new scheduler using every available core
     pub fn new() -> Self {
         Self {
             systems: Vec::new(),
+            batches: Vec::new(),
+            dirty: false,
+            thread_count: std::thread::available_parallelism().map_or(1, |count| count.get()),
+            pool: None,
+            profiler: None,
+            states: Vec::new(),
+        }
+    }
+
+    /// Apply transitions of state machine `S` every frame even if no
+    /// system depends on it yet; `on_enter`/`on_exit`/`on_update` do this
+    /// implicitly. The world needs a `States<S>` resource.
+    pub fn add_state<S: State>(&mut self) {
+        add_state_driver::<S>(&mut self.states);
+    }
+
+    /// Time every system run, keeping statistics over the last `window_frames` frames
+    pub fn enable_profiling(&mut self, window_frames: usize) {
+        self.profiler = Some(Profiler::new(window_frames));
+    }
+
+    /// Stop timing systems and drop the collected timings
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// Collected timings, if profiling is enabled
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Collected timings, e.g. to `record` spans measured outside the scheduler
+    pub fn profiler_mut(&mut self) -> Option<&mut Profiler> {
+        self.profiler.as_mut()
+    }
+
+    /// Limit parallel batches to `count` threads; 1 runs everything on the calling thread
+    pub fn set_thread_count(&mut self, count: usize) {
+        let count = count.max(1);
+        if count != self.thread_count {
+            self.thread_count = count;
+            self.pool = None;
+        }
+    }
+
+    /// Start the worker pool unless batches run on the calling thread; if
+    /// threads cannot be spawned, batches run there too
+    fn start_pool(&mut self) {
+        if self.pool.is_none() && self.thread_count > 1 {
+            self.pool = ThreadPoolBuilder::new()
+                .num_threads(self.thread_count)
+                .thread_name(|index| format!("rocket-worker-{}", index))
+                .build()
+                .ok();
         }
     }
 
-    /// Add a system to the scheduler
-    pub fn add_system(&mut self, system: Box<dyn System>) {
-        self.systems.push(system);
+    /// Add a system to the `Update` stage; chain `in_stage`, `before`,
+    /// `after` or `label` on the result to configure it
+    pub fn add_system(&mut self, system: Box<dyn System>) -> SystemConfig<'_> {
+        self.push(SystemKind::Exclusive(system))
     }
     
     /// Add a query-based system to the scheduler
-    pub fn add_query_system<T: QuerySystem + 'static>(&mut self, system: T) {
-        self.systems.push(Box::new(QuerySystemAdapter::new(system)));
+    pub fn add_query_system<T: QuerySystem + 'static>(&mut self, system: T) -> SystemConfig<'_> {
+        self.add_system(Box::new(QuerySystemAdapter::new(system)))
+    }
+
+    /// Add a system that may run concurrently with others it does not
+    /// conflict with, judged by its `access()`
+    pub fn add_parallel_system<T: ParallelSystem + 'static>(&mut self, system: T) -> SystemConfig<'_> {
+        let access = system.access();
+        self.push(SystemKind::Parallel(Box::new(system), access))
+    }
+
+    fn push(&mut self, system: SystemKind) -> SystemConfig<'_> {
+        self.dirty = true;
+        self.systems.push(SystemEntry::new(system));
+        let entry = self.systems.last_mut().unwrap();
+        SystemConfig { entry, states: &mut self.states }
+    }
+
+    /// Resume the systems named or labelled `label`; false if there are none
+    pub fn enable_system(&mut self, label: &str) -> bool {
+        self.set_enabled(label, true)
+    }
+
+    /// Skip the systems named or labelled `label` until re-enabled; false if there are none
+    pub fn disable_system(&mut self, label: &str) -> bool {
+        self.set_enabled(label, false)
+    }
+
+    fn set_enabled(&mut self, label: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for entry in self.systems.iter_mut().filter(|entry| entry.has_label(label)) {
+            entry.enabled = enabled;
+            found = true;
+        }
+        found
+    }
+
+    /// Whether the system named `name` is enabled, or `None` if there is no such system
+    pub fn is_system_enabled(&self, name: &str) -> Option<bool> {
+        self.systems.iter().find(|entry| entry.name() == name).map(|entry| entry.enabled)
+    }
+
+    /// Remove the systems named or labelled `label`, returning how many were removed.
+    ///
+    /// Other systems' `before`/`after` constraints on labels nobody carries
+    /// any more are dropped with them.
+    pub fn remove_system(&mut self, label: &str) -> usize {
+        let count = self.systems.len();
+        self.systems.retain(|entry| !entry.has_label(label));
+        let removed = count - self.systems.len();
+        if removed > 0 {
+            let carried: HashSet<&'static str> = self.systems
+                .iter()
+                .flat_map(|entry| entry.labels.iter().copied().chain([entry.name()]))
+                .collect();
+            for entry in &mut self.systems {
+                entry.before.retain(|label| carried.contains(label));
+                entry.after.retain(|label| carried.contains(label));
+            }
+            self.batches.clear();
+            self.dirty = true;
+        }
+        removed
+    }
+
+    /// Resolve the execution order from stages and `before`/`after` constraints.
+    ///
+    /// `update` does this on demand; call it directly to report errors
+    /// without panicking.
+    pub fn build(&mut self) -> Result<(), ScheduleError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let order = self.resolve_order()?;
+        let mut slots: Vec<Option<SystemEntry>> = self.systems.drain(..).map(Some).collect();
+        self.systems = order.into_iter().map(|index| slots[index].take().unwrap()).collect();
+        self.batches = self.resolve_batches();
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Group the sorted systems into batches.
+    ///
+    /// Each system depends on every earlier system of its stage that it
+    /// conflicts with or is explicitly ordered after; it joins the batch
+    /// right after its latest dependency.
+    fn resolve_batches(&self) -> Vec<(Stage, Batch)> {
+        let mut batches = Vec::new();
+        for stage in Stage::ALL {
+            let members: Vec<usize> = (0..self.systems.len())
+                .filter(|&i| self.systems[i].stage == stage && self.systems[i].hook.is_none())
+                .collect();
+            let mut levels: Vec<usize> = Vec::with_capacity(members.len());
+            let mut stage_batches: Vec<Batch> = Vec::new();
+            for (position, &index) in members.iter().enumerate() {
+                let entry = &self.systems[index];
+                let level = members[..position]
+                    .iter()
+                    .zip(&levels)
+                    .filter(|&(&earlier, _)| {
+                        let earlier = &self.systems[earlier];
+                        !entry.is_compatible(earlier) || self.is_ordered(earlier, entry)
+                    })
+                    .map(|(_, &level)| level + 1)
+                    .max()
+                    .unwrap_or(0);
+                levels.push(level);
+                if level == stage_batches.len() {
+                    stage_batches.push(Vec::new());
+                }
+                stage_batches[level].push(index);
+            }
+            batches.extend(stage_batches.into_iter().map(|batch| (stage, batch)));
+        }
+        batches
     }
 
-    /// Update all systems in order
+    /// Whether a `before`/`after` constraint links `first` to `then`
+    fn is_ordered(&self, first: &SystemEntry, then: &SystemEntry) -> bool {
+        first.before.iter().any(|&label| then.has_label(label))
+            || then.after.iter().any(|&label| first.has_label(label))
+    }
+
+    /// Indices of `systems` in execution order
+    fn resolve_order(&self) -> Result<Vec<usize>, ScheduleError> {
+        let count = self.systems.len();
+        // edges[a] holds every system that must run after system a
+        let mut edges = vec![Vec::new(); count];
+        for (index, entry) in self.systems.iter().enumerate() {
+            let constraints = entry.before.iter().map(|label| (label, true))
+                .chain(entry.after.iter().map(|label| (label, false)));
+            for (&label, is_before) in constraints {
+                let mut found = false;
+                for (other, target) in self.systems.iter().enumerate() {
+                    if other == index || !target.has_label(label) {
+                        continue;
+                    }
+                    found = true;
+                    let (first, then) = if is_before { (index, other) } else { (other, index) };
+                    let (first_stage, then_stage) = (self.systems[first].stage, self.systems[then].stage);
+                    if first_stage > then_stage {
+                        return Err(ScheduleError::StageConflict { system: entry.name(), label });
+                    }
+                    if first_stage == then_stage {
+                        edges[first].push(then);
+                    }
+                }
+                if !found {
+                    return Err(ScheduleError::UnknownLabel { system: entry.name(), label });
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(count);
+        for stage in Stage::ALL {
+            let members: Vec<usize> = (0..count).filter(|&i| self.systems[i].stage == stage).collect();
+            let mut in_degree = vec![0; count];
+            for &member in &members {
+                for &next in &edges[member] {
+                    in_degree[next] += 1;
+                }
+            }
+
+            // Kahn's algorithm, always taking the earliest-added ready system
+            let mut remaining = members.clone();
+            while let Some(position) = remaining.iter().position(|&i| in_degree[i] == 0) {
+                let next = remaining.remove(position);
+                for &after in &edges[next] {
+                    in_degree[after] -= 1;
+                }
+                order.push(next);
+            }
+
+            if !remaining.is_empty() {
+                return Err(ScheduleError::Cycle { stage, systems: self.find_cycle(&edges, &remaining) });
+            }
+        }
+        Ok(order)
+    }
+
+    /// Names along one cycle among `remaining`, found by walking unresolved
+    /// predecessors (every remaining system has one) until one repeats
+    fn find_cycle(&self, edges: &[Vec<usize>], remaining: &[usize]) -> Vec<&'static str> {
+        let mut path = vec![remaining[0]];
+        loop {
+            let current = *path.last().unwrap();
+            let predecessor = remaining
+                .iter()
+                .copied()
+                .find(|&candidate| edges[candidate].contains(&current))
+                .unwrap();
+            if let Some(start) = path.iter().position(|&i| i == predecessor) {
+                let mut cycle = path.split_off(start);
+                cycle.reverse();
+                // Start from the earliest-added system so the report is stable
+                let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+                cycle.rotate_left(first);
+                return cycle.into_iter().map(|i| self.systems[i].name()).collect();
+            }
+            path.push(predecessor);
+        }
+    }
+
+    /// Start a new frame (advance `Time`, swap event buffers), then update all systems stage by stage.
+    /// Commands recorded by a system are applied before the next one runs.
+    ///
+    /// `FixedUpdate` runs zero or more times per frame, as many steps as
+    /// `FixedTime` has banked, and its systems get `FixedTime::timestep` as
+    /// `dt`; every other stage runs once with the frame's `dt`.
+    ///
+    /// Queued state transitions apply between `PreUpdate` and `FixedUpdate`,
+    /// running the `on_exit` and `on_enter` systems they trigger.
+    ///
+    /// Each system's `Added`/`Changed` queries see what changed since that
+    /// system's previous run, including changes made outside the scheduler.
+    ///
+    /// # Panics
+    ///
+    /// If the system order cannot be resolved (see `build`).
     pub fn update(&mut self, world: &mut World, dt: f32) {
-        for system in &mut self.systems {
-            system.update(world, dt);
+        if let Err(error) = self.build() {
+            panic!("{}", error);
+        }
+        if let Some(profiler) = &mut self.profiler {
+            profiler.begin_frame();
         }
+
+        world.init_resource::<Time>().advance(dt);
+        world.update_events();
+        world.apply_commands();
+
+        for stage in Stage::ALL {
+            if stage == Stage::FixedUpdate {
+                let fixed_time = world.init_resource::<FixedTime>();
+                let (steps, timestep) = (fixed_time.accumulate(dt), fixed_time.timestep);
+                for _ in 0..steps {
+                    self.run_stage(stage, world, timestep);
+                }
+            } else {
+                self.run_stage(stage, world, dt);
+            }
+            if stage == Stage::PreUpdate {
+                self.apply_state_transitions(world, dt);
+            }
+        }
+    }
+
+    /// Apply each state machine's queued transition: `on_exit` systems,
+    /// state-scoped despawns, then `on_enter` systems
+    fn apply_state_transitions(&mut self, world: &mut World, dt: f32) {
+        for driver in self.states.clone() {
+            let Some(transition) = (driver.apply)(world) else {
+                continue;
+            };
+            let hooked = |on_enter: bool, systems: &[SystemEntry]| -> Vec<usize> {
+                (0..systems.len())
+                    .filter(|&index| systems[index].hook.as_ref().is_some_and(|hook| {
+                        hook.state == driver.state && hook.on_enter == on_enter && hook.fires(&transition)
+                    }))
+                    .collect()
+            };
+
+            for index in hooked(false, &self.systems) {
+                self.run_batch(&[index], world, dt);
+            }
+            if let Some(exited) = &transition.exited {
+                (driver.despawn_scoped)(world, &**exited);
+            }
+            for index in hooked(true, &self.systems) {
+                self.run_batch(&[index], world, dt);
+            }
+        }
+    }
+
+    /// Run every system of one stage once, batch by batch
+    fn run_stage(&mut self, stage: Stage, world: &mut World, dt: f32) {
+        for batch_index in 0..self.batches.len() {
+            if self.batches[batch_index].0 == stage {
+                let batch = self.batches[batch_index].1.clone();
+                self.run_batch(&batch, world, dt);
+            }
+        }
+    }
+
+    /// Run the systems of a batch whose run conditions hold, then apply
+    /// their commands and advance the change tick
+    fn run_batch(&mut self, batch: &[usize], world: &mut World, dt: f32) {
+        let this_run = world.change_tick();
+        let runnable: Batch = batch
+            .iter()
+            .copied()
+            .filter(|&index| self.systems[index].should_run(world))
+            .collect();
+        match runnable.as_slice() {
+            [] => {}
+            &[index] if matches!(self.systems[index].system, SystemKind::Exclusive(_)) => {
+                let entry = &mut self.systems[index];
+                world.set_last_change_tick(entry.last_run);
+                let start = Instant::now();
+                if let SystemKind::Exclusive(system) = &mut entry.system {
+                    system.update(world, dt);
+                }
+                if let Some(profiler) = &mut self.profiler {
+                    profiler.record(entry.name(), start);
+                }
+            }
+            runnable => {
+                self.start_pool();
+                let timings = run_parallel_batch(&mut self.systems, runnable, world, dt, self.pool.as_ref());
+                if let Some(profiler) = &mut self.profiler {
+                    for (name, start, duration, thread) in timings {
+                        profiler.record_on(name, start, duration, thread);
+                    }
+                }
+            }
+        }
+        world.apply_commands();
+        for &index in &runnable {
+            self.systems[index].last_run = this_run;
+        }
+        world.increment_change_tick();
     }
 
     /// Get the number of systems
@@ -69,9 +668,94 @@ impl Scheduler {
         self.systems.len()
     }
 
-    /// Get system names for debugging
+    /// Get system names for debugging, in execution order once the schedule
+    /// is built (insertion order before that)
     pub fn system_names(&self) -> Vec<&str> {
-        self.systems.iter().map(|s| s.name()).collect()
+        self.systems.iter().map(|entry| entry.name()).collect()
+    }
+
+    /// Names of the systems in one stage, in the same order as `system_names`
+    pub fn stage_system_names(&self, stage: Stage) -> Vec<&str> {
+        self.systems
+            .iter()
+            .filter(|entry| entry.stage == stage)
+            .map(|entry| entry.name())
+            .collect()
+    }
+
+    /// Resolve the schedule and describe it stage by stage, one line per
+    /// stage; systems sharing a parallel batch are grouped in brackets
+    pub fn resolved_order(&mut self) -> Result<String, ScheduleError> {
+        self.build()?;
+        let mut order = String::from("System order:");
+        for stage in Stage::ALL {
+            let batches: Vec<String> = self.batches
+                .iter()
+                .filter(|(batch_stage, _)| *batch_stage == stage)
+                .map(|(_, batch)| {
+                    let names: Vec<&str> = batch.iter().map(|&index| self.systems[index].name()).collect();
+                    match names.as_slice() {
+                        [name] => name.to_string(),
+                        names => format!("[{}]", names.join(" | ")),
+                    }
+                })
+                .collect();
+            if !batches.is_empty() {
+                order.push_str(&format!("\n  {:?}: {}", stage, batches.join(" -> ")));
+            }
+        }
+        let hooked: Vec<&str> = self.systems
+            .iter()
+            .filter(|entry| entry.hook.is_some())
+            .map(|entry| entry.name())
+            .collect();
+        if !hooked.is_empty() {
+            order.push_str(&format!("\n  On state transitions: {}", hooked.join(", ")));
+        }
+        Ok(order)
+    }
+}
+
+/// Start, duration and thread of a system run on a worker
+type Timing = (&'static str, Instant, Duration, ThreadId);
+
+/// Run a batch of parallel systems, lending each the data it writes
+fn run_parallel_batch(systems: &mut [SystemEntry], batch: &[usize], world: &mut World, dt: f32, pool: Option<&ThreadPool>) -> Vec<Timing> {
+    let mut jobs = Vec::with_capacity(batch.len());
+    for (index, entry) in systems.iter_mut().enumerate() {
+        if !batch.contains(&index) {
+            continue;
+        }
+        let name = entry.name();
+        if let SystemKind::Parallel(system, access) = &mut entry.system {
+            let lent = LentData::take(world, access, entry.last_run);
+            jobs.push((system, &*access, name, lent));
+        }
+    }
+
+    let shared: &World = world;
+    let returned = run_pool(jobs, pool, |(system, access, name, lent)| {
+        let mut view = lent.into_view(shared, access, name);
+        let start = Instant::now();
+        system.run(&mut view, dt);
+        let timing = (name, start, start.elapsed(), std::thread::current().id());
+        (view.into_lent(), timing)
+    });
+
+    returned
+        .into_iter()
+        .map(|(lent, timing)| {
+            lent.restore(world);
+            timing
+        })
+        .collect()
+}
+
+/// Register state machine `S` unless it already is
+fn add_state_driver<S: State>(states: &mut Vec<StateDriver>) {
+    let driver = StateDriver::of::<S>();
+    if !states.iter().any(|known| known.state == driver.state) {
+        states.push(driver);
     }
 }
 
@@ -80,3 +764,266 @@ impl Default for Scheduler {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+    use std::sync::{Arc, Barrier, Mutex};
+    use crate::components::{Entity, Position};
+    use crate::storage::Component;
+    use crate::systems::WorldView;
+    use super::*;
+
+    struct Named(&'static str);
+
+    impl System for Named {
+        fn update(&mut self, _world: &mut World, _dt: f32) {}
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_stages_and_labels_order_systems() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Named("Transform"))).in_stage(Stage::PostUpdate);
+        scheduler.add_system(Box::new(Named("Movement"))).after("physics");
+        scheduler.add_system(Box::new(Named("Physics"))).in_stage(Stage::FixedUpdate).label("physics");
+        scheduler.add_system(Box::new(Named("Sync"))).in_stage(Stage::FixedUpdate).before("physics");
+        scheduler.add_system(Box::new(Named("Ai"))).before("Movement");
+        scheduler.add_system(Box::new(Named("Input"))).in_stage(Stage::PreUpdate);
+
+        scheduler.build().unwrap();
+        assert_eq!(scheduler.system_names(), vec!["Input", "Sync", "Physics", "Ai", "Movement", "Transform"]);
+        assert_eq!(scheduler.stage_system_names(Stage::FixedUpdate), vec!["Sync", "Physics"]);
+        let order = scheduler.resolved_order().unwrap();
+        assert!(order.contains("\n  FixedUpdate: Sync -> Physics"), "{}", order);
+    }
+
+    #[test]
+    fn test_fixed_update_is_frame_rate_independent() {
+        use crate::components::Velocity;
+        use crate::systems::MovementSystem;
+
+        let mut results = Vec::new();
+        for fps in [30, 60, 144] {
+            let mut world = World::new();
+            let entity = world.spawn((Position::new(0.0, 0.0), Velocity::new(90.0, -45.0)));
+            let mut scheduler = Scheduler::new();
+            scheduler.add_query_system(MovementSystem::new()).in_stage(Stage::FixedUpdate);
+            for _ in 0..fps * 2 {
+                scheduler.update(&mut world, 1.0 / fps as f32);
+            }
+            results.push(world.get::<Position>(entity).map(|p| p.as_tuple()).unwrap());
+        }
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[1], results[2]);
+    }
+
+    /// Parallel system that writes one component and meets a barrier
+    struct Worker<T> {
+        barrier: Option<Arc<Barrier>>,
+        name: &'static str,
+        _marker: PhantomData<fn() -> T>,
+    }
+
+    impl<T> Worker<T> {
+        fn new(name: &'static str, barrier: Option<Arc<Barrier>>) -> Self {
+            Self { barrier, name, _marker: PhantomData }
+        }
+    }
+
+    impl<T: Component + Default> ParallelSystem for Worker<T> {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().write::<T>().read::<Position>()
+        }
+
+        fn run(&mut self, world: &mut WorldView<'_>, _dt: f32) {
+            if let Some(barrier) = &self.barrier {
+                barrier.wait();
+            }
+            for (entity, _position) in world.query::<(Entity, &Position)>() {
+                world.commands().entity(entity).insert(T::default());
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+    }
+
+    #[derive(Default)]
+    struct Brain;
+    impl Component for Brain {}
+
+    #[derive(Default)]
+    struct Pose;
+    impl Component for Pose {}
+
+    #[test]
+    fn test_disjoint_parallel_systems_run_concurrently() {
+        let mut world = World::new();
+        let entity = world.spawn((Position::new(0.0, 0.0),));
+
+        // Both systems wait for each other, so this only finishes if they overlap
+        let barrier = Arc::new(Barrier::new(2));
+        let mut scheduler = Scheduler::new();
+        scheduler.set_thread_count(2);
+        scheduler.add_parallel_system(Worker::<Brain>::new("Ai", Some(barrier.clone())));
+        scheduler.add_parallel_system(Worker::<Pose>::new("Animation", Some(barrier)));
+        scheduler.add_parallel_system(Worker::<Brain>::new("AiCleanup", None));
+        scheduler.add_system(Box::new(Named("Exclusive")));
+        scheduler.update(&mut world, 1.0 / 60.0);
+
+        let batches: Vec<Vec<&str>> = scheduler.batches
+            .iter()
+            .map(|(_, batch)| batch.iter().map(|&i| scheduler.systems[i].name()).collect())
+            .collect();
+        assert_eq!(batches, vec![vec!["Ai", "Animation"], vec!["AiCleanup"], vec!["Exclusive"]]);
+        assert!(world.has::<Brain>(entity) && world.has::<Pose>(entity));
+    }
+
+    /// Parallel system that records which thread runs it
+    struct ThreadLog(&'static str, Arc<Mutex<HashSet<ThreadId>>>);
+
+    impl ParallelSystem for ThreadLog {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().read::<Position>()
+        }
+
+        fn run(&mut self, _world: &mut WorldView<'_>, _dt: f32) {
+            self.1.lock().unwrap().insert(std::thread::current().id());
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_parallel_batches_reuse_the_same_workers() {
+        let mut world = World::new();
+        let threads = Arc::new(Mutex::new(HashSet::new()));
+        let mut scheduler = Scheduler::new();
+        scheduler.set_thread_count(2);
+        scheduler.add_parallel_system(ThreadLog("First", threads.clone()));
+        scheduler.add_parallel_system(ThreadLog("Second", threads.clone()));
+        for _ in 0..20 {
+            scheduler.update(&mut world, 1.0 / 60.0);
+        }
+        assert!(threads.lock().unwrap().len() <= 2);
+    }
+
+    struct Sneaky;
+
+    impl ParallelSystem for Sneaky {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().read::<Position>()
+        }
+
+        fn run(&mut self, world: &mut WorldView<'_>, _dt: f32) {
+            for _ in world.query_mut::<&mut Position>() {}
+        }
+
+        fn name(&self) -> &'static str {
+            "Sneaky"
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "system Sneaky accesses")]
+    fn test_undeclared_access_panics() {
+        let mut world = World::new();
+        world.spawn((Position::new(0.0, 0.0),));
+        let mut scheduler = Scheduler::new();
+        scheduler.set_thread_count(1);
+        scheduler.add_parallel_system(Sneaky);
+        scheduler.update(&mut world, 1.0 / 60.0);
+    }
+
+    #[test]
+    fn test_schedule_errors() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Named("A"))).after("C");
+        scheduler.add_system(Box::new(Named("B"))).after("A");
+        scheduler.add_system(Box::new(Named("C"))).after("B");
+        scheduler.add_system(Box::new(Named("D")));
+        let error = scheduler.build().unwrap_err();
+        assert_eq!(error, ScheduleError::Cycle { stage: Stage::Update, systems: vec!["A", "B", "C"] });
+        assert_eq!(error.to_string(), "system order cycle in Update: A -> B -> C -> A");
+
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Named("A"))).before("Missing");
+        assert!(matches!(scheduler.build(), Err(ScheduleError::UnknownLabel { label: "Missing", .. })));
+
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Named("Late"))).in_stage(Stage::Render).before("Early");
+        scheduler.add_system(Box::new(Named("Early"))).in_stage(Stage::PreUpdate);
+        assert!(matches!(scheduler.build(), Err(ScheduleError::StageConflict { .. })));
+    }
+
+    /// Names of the systems that ran, in order
+    #[derive(Default)]
+    struct Ran(Vec<&'static str>);
+
+    impl crate::resources::Resource for Ran {}
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Mode {
+        Playing,
+        Paused,
+    }
+
+    impl crate::resources::Resource for Mode {}
+
+    struct Logged(&'static str);
+
+    impl System for Logged {
+        fn update(&mut self, world: &mut World, _dt: f32) {
+            world.init_resource::<Ran>().0.push(self.0);
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_run_conditions_and_toggles() {
+        use crate::systems::condition::{on_timer, resource_equals};
+
+        let mut world = World::new();
+        world.insert_resource(Mode::Playing);
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(Box::new(Logged("Physics"))).label("gameplay").run_if(resource_equals(Mode::Playing));
+        scheduler.add_system(Box::new(Logged("Ai"))).label("gameplay").after("Physics");
+        scheduler.add_system(Box::new(Logged("Overlay")));
+        scheduler.add_system(Box::new(Logged("Autosave"))).run_if(on_timer(0.5));
+        let frame = |scheduler: &mut Scheduler, world: &mut World| {
+            scheduler.update(world, 0.25);
+            std::mem::take(&mut world.resource_mut::<Ran>().unwrap().0)
+        };
+
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Physics", "Ai", "Overlay", "Autosave"]);
+        world.insert_resource(Mode::Paused);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Ai", "Overlay"]);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Ai", "Overlay", "Autosave"]);
+
+        // Disabling by label pauses the whole group; the overlay keeps running
+        world.insert_resource(Mode::Playing);
+        assert!(scheduler.disable_system("gameplay"));
+        assert_eq!(scheduler.is_system_enabled("Ai"), Some(false));
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Overlay"]);
+        assert!(scheduler.enable_system("Ai"));
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Ai", "Overlay", "Autosave"]);
+        assert!(!scheduler.disable_system("Missing"));
+
+        // Removing Physics drops Ai's ordering constraint on it
+        assert_eq!(scheduler.remove_system("Physics"), 1);
+        assert_eq!(scheduler.is_system_enabled("Physics"), None);
+        assert_eq!(frame(&mut scheduler, &mut world), vec!["Ai", "Overlay"]);
+        assert_eq!(scheduler.system_names(), vec!["Ai", "Overlay", "Autosave"]);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::world::World;
 use crate::systems::System;
 
-/// Optional system to push ECS velocity into physics bodies each frame
-/// so bodies move even without external forces/input.
+/// Optional system to push ECS `Position`/`Velocity` edits into physics bodies
+/// before the physics step. Only components changed since the last physics
+/// write-back are synced.
 pub struct VelocitySyncSystem;
 
 impl VelocitySyncSystem {
@@ -11,13 +12,7 @@ impl VelocitySyncSystem {
 
 impl System for VelocitySyncSystem {
     fn update(&mut self, world: &mut World, _dt: f32) {
-        for (&entity, velocity) in &world.velocities {
-            if let Some(&body_handle) = world.entity_to_body.get(&entity) {
-                if let Some(body) = world.physics_world.get_mut(body_handle) {
-                    body.set_linvel(nalgebra::Vector2::new(velocity.x, velocity.y), true);
-                }
-            }
-        }
+        world.sync_changes_to_physics();
     }
 
     fn name(&self) -> &'static str { "VelocitySyncSystem" }
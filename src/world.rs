@@ -1,15 +1,52 @@
+use std::any::TypeId;
 use std::collections::HashMap;
 use crate::components::*;
+use crate::components::ColliderShape; // not the alias in the Rapier prelude
+use crate::change_detection::Tick;
+use crate::commands::{CommandQueue, Commands};
+use crate::entity::Entities;
+use crate::events::{
+    CollisionStarted, CollisionStopped, ContactForce, ContactPoint, EntityDespawned, Event, EventReader, Events,
+    OutOfBounds, SensorEntered, SensorExited,
+};
+use crate::resources::{BoundsBehavior, FixedTime, PhysicsConfig, Resource, Resources, ScreenSize, Time, WorldBounds};
+use crate::joints::{apply_motor, EntityJoint, JointDesc, JointMotor};
+use crate::spatial::{PointProjection, RayHit, SpatialFilter};
+use crate::storage::{Bundle, Component, ComponentStorage, Components, StorageIter, StorageIterMut};
+use crate::systems::query::{QueryData, QueryFilter, QueryIter, QuerySource, ReadOnlyQueryData, With, Without};
+use rapier2d::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
+use rapier2d::crossbeam::channel::{self, Receiver};
+use rapier2d::parry::query::ShapeCastOptions;
+use rapier2d::pipeline::QueryFilter as RapierQueryFilter;
 use rapier2d::prelude::*;
 use nalgebra::Vector2;
 
-/// World contains all components in HashMaps + Rapier2D Physics World
+/// Callbacks run when a component is inserted on or removed from an entity.
+///
+/// `on_remove` runs while the old value is still attached, including before
+/// a replacing insert and before a despawn; `on_insert` runs after the new
+/// value is attached.
+#[derive(Clone, Copy)]
+pub struct ComponentHooks {
+    pub on_insert: fn(&mut World, Entity),
+    pub on_remove: fn(&mut World, Entity),
+}
+
+/// World contains all entities, a type-keyed component registry, global resources + Rapier2D Physics World
 pub struct World {
-    pub next_entity_id: Entity,
-    pub positions: HashMap<Entity, Position>,
-    pub velocities: HashMap<Entity, Velocity>,
-    pub sprites: HashMap<Entity, Sprite>,
-    pub texture_sprites: HashMap<Entity, TextureSprite>,
+    pub entities: Entities,
+    pub components: Components,
+    pub resources: Resources,
+    /// Buffer swap functions for every registered event type
+    event_updaters: HashMap<TypeId, fn(&mut Resources)>,
+    /// Deferred commands recorded through `Commands`
+    command_queue: CommandQueue,
+    /// Insert/remove callbacks for component types with world-side indices
+    component_hooks: HashMap<TypeId, ComponentHooks>,
+    /// Entities by `Name`, in insertion order
+    name_index: HashMap<String, Vec<Entity>>,
+    /// Entities by tag, in insertion order
+    tag_index: HashMap<String, Vec<Entity>>,
     pub sprite_atlas: Option<SpriteAtlas>,
     
     // Rapier2D Physics World
@@ -23,30 +60,61 @@ pub struct World {
     pub impulse_joint_set: ImpulseJointSet,
     pub multibody_joint_set: MultibodyJointSet,
     pub ccd_solver: CCDSolver,
+    /// Collider lookups for physics queries, as of the last physics step
+    pub query_pipeline: QueryPipeline,
     pub physics_hooks: (),
-    pub event_handler: (),
+    pub event_handler: ChannelEventCollector,
+    // What `event_handler` collects during a step
+    collision_events: Receiver<CollisionEvent>,
+    contact_force_events: Receiver<ContactForceEvent>,
     
     // Map Entity IDs to Rapier RigidBodyHandle
     pub entity_to_body: HashMap<Entity, RigidBodyHandle>,
     pub body_to_entity: HashMap<RigidBodyHandle, Entity>,
+    // Joints made by `add_joint`, kept so they can be listed and saved;
+    // Rapier removes the joint itself along with either body
+    joints: HashMap<ImpulseJointHandle, EntityJoint>,
+
+    // Owners of colliders removed since the last physics step, which still
+    // reports them in `CollisionStopped` events, and whether they were sensors
+    removed_colliders: HashMap<ColliderHandle, (Entity, bool)>,
+    // Change tick of the last physics write-back; later Position/Velocity
+    // changes come from gameplay code and are pushed into Rapier
+    physics_sync_tick: Tick,
+    // Entity holding the edge colliders of `BoundsBehavior::Colliders`, and
+    // the bounds they were built for
+    bounds_colliders: Option<(Entity, WorldBounds)>,
 }
 
 impl World {
     /// Create a new world
     pub fn new() -> Self {
-        let mut integration_parameters = IntegrationParameters::default();
-        integration_parameters.dt = 1.0 / 60.0; // 60 FPS
-        
-        // Configure CCD parameters for better fast-object collision handling
-        integration_parameters.max_ccd_substeps = 4; // More substeps for better CCD
-        integration_parameters.min_ccd_dt = 1.0 / 240.0; // Smaller minimum CCD timestep for accuracy
-
-        Self {
-            next_entity_id: 0,
-            positions: HashMap::new(),
-            velocities: HashMap::new(),
-            sprites: HashMap::new(),
-            texture_sprites: HashMap::new(),
+        let integration_parameters = IntegrationParameters {
+            dt: 1.0 / 60.0, // 60 FPS
+            // Configure CCD parameters for better fast-object collision handling
+            max_ccd_substeps: 4, // More substeps for better CCD
+            min_ccd_dt: 1.0 / 240.0, // Smaller minimum CCD timestep for accuracy
+            ..Default::default()
+        };
+
+        let mut resources = Resources::new();
+        resources.insert(Time::default());
+        resources.insert(FixedTime::new(integration_parameters.dt));
+        resources.insert(ScreenSize::default());
+        resources.insert(PhysicsConfig::default());
+        resources.insert(WorldBounds::default());
+        let (collision_sender, collision_events) = channel::unbounded();
+        let (contact_force_sender, contact_force_events) = channel::unbounded();
+
+        let mut world = Self {
+            entities: Entities::new(),
+            components: Components::new(),
+            resources,
+            event_updaters: HashMap::new(),
+            command_queue: CommandQueue::default(),
+            component_hooks: HashMap::new(),
+            name_index: HashMap::new(),
+            tag_index: HashMap::new(),
             sprite_atlas: None,
             
             physics_world: RigidBodySet::new(),
@@ -59,12 +127,29 @@ impl World {
             impulse_joint_set: ImpulseJointSet::new(),
             multibody_joint_set: MultibodyJointSet::new(),
             ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
             physics_hooks: (),
-            event_handler: (),
+            event_handler: ChannelEventCollector::new(collision_sender, contact_force_sender),
+            collision_events,
+            contact_force_events,
             
             entity_to_body: HashMap::new(),
             body_to_entity: HashMap::new(),
-        }
+            joints: HashMap::new(),
+
+            removed_colliders: HashMap::new(),
+            physics_sync_tick: 0,
+            bounds_colliders: None,
+        };
+        world.set_component_hooks::<Name>(ComponentHooks {
+            on_insert: index_name,
+            on_remove: unindex_name,
+        });
+        world.set_component_hooks::<Tags>(ComponentHooks {
+            on_insert: index_tags,
+            on_remove: unindex_tags,
+        });
+        world
     }
 
     /// Set the sprite atlas
@@ -74,135 +159,1025 @@ impl World {
 
     /// Create a new entity
     pub fn create_entity(&mut self) -> Entity {
-        let entity = self.next_entity_id;
-        self.next_entity_id += 1;
+        self.entities.alloc()
+    }
+
+    /// Create a new entity with a bundle of components, e.g. `world.spawn((Position::new(0.0, 0.0), Velocity::zero()))`
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.create_entity();
+        self.insert_bundle(entity, bundle);
         entity
     }
 
-    /// Add position component to an entity
+    /// Insert a bundle of components on an entity (ignored for dead entities)
+    pub fn insert_bundle<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        if self.is_alive(entity) {
+            bundle.insert_into(self, entity);
+        }
+    }
+
+    /// Get a handle for recording deferred spawn/despawn/insert/remove commands.
+    /// The handle does not borrow the world, so it can be used inside query loops.
+    pub fn commands(&self) -> Commands {
+        Commands::new(self.command_queue.clone(), self.entities.reserver())
+    }
+
+    /// Apply every queued command in recording order
+    pub fn apply_commands(&mut self) {
+        loop {
+            let queued = std::mem::take(&mut *self.command_queue.lock().unwrap());
+            if queued.is_empty() {
+                break;
+            }
+
+            // Reserved entities must be live before their components are inserted
+            self.entities.flush();
+            for command in queued {
+                command(self);
+            }
+        }
+        self.entities.flush();
+    }
+
+    /// Check whether an entity handle still refers to a live entity
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// Register a component type so its storage exists before first insert
+    pub fn register_component<T: Component>(&mut self) {
+        self.components.register::<T>();
+    }
+
+    /// Insert a component on an entity, replacing any previous value.
+    /// Ignored for dead entities.
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) {
+        if !self.is_alive(entity) {
+            return;
+        }
+        match self.component_hooks.get(&TypeId::of::<T>()).copied() {
+            Some(hooks) => {
+                if self.has::<T>(entity) {
+                    (hooks.on_remove)(self, entity);
+                }
+                self.components.insert(entity, component);
+                (hooks.on_insert)(self, entity);
+            }
+            None => {
+                self.components.insert(entity, component);
+            }
+        }
+    }
+
+    /// Get a component of an entity
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.components.get::<T>(entity)
+    }
+
+    /// Get a component of an entity mutably, marking it changed
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.components.get_mut::<T>(entity)
+    }
+
+    /// Remove a component from an entity, returning it
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        if let Some(hooks) = self.component_hooks.get(&TypeId::of::<T>()).copied()
+            && self.has::<T>(entity)
+        {
+            (hooks.on_remove)(self, entity);
+        }
+        self.components.storage_mut::<T>()?.remove(entity)
+    }
+
+    /// Check whether an entity has a component
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Get the storage of a component type, if it was ever registered
+    pub fn storage<T: Component>(&self) -> Option<&ComponentStorage<T>> {
+        self.components.storage::<T>()
+    }
+
+    /// Iterate over all entities that have a component
+    pub fn iter<T: Component>(&self) -> StorageIter<'_, T> {
+        self.components.iter::<T>()
+    }
+
+    /// Iterate mutably over all entities that have a component, marking them changed
+    pub fn iter_mut<T: Component>(&mut self) -> StorageIterMut<'_, T> {
+        self.components.iter_mut::<T>()
+    }
+
+    /// Tick stamped on components inserted or mutated now
+    pub fn change_tick(&self) -> Tick {
+        self.components.change_tick()
+    }
+
+    /// Advance the change tick, returning the new value.
+    /// The `Scheduler` does this after every system.
+    pub fn increment_change_tick(&mut self) -> Tick {
+        self.components.increment_change_tick()
+    }
+
+    /// Tick that `Added`/`Changed` filters compare against
+    pub fn last_change_tick(&self) -> Tick {
+        self.components.last_change_tick()
+    }
+
+    /// Set the tick that `Added`/`Changed` filters compare against.
+    /// The `Scheduler` sets it to each system's previous run.
+    pub fn set_last_change_tick(&mut self, tick: Tick) {
+        self.components.set_last_change_tick(tick);
+    }
+
+    /// For code running outside the `Scheduler` (editors, tools): make the
+    /// next `Added`/`Changed` queries only report changes made after this call
+    pub fn clear_trackers(&mut self) {
+        let now = self.change_tick();
+        self.set_last_change_tick(now);
+        self.increment_change_tick();
+    }
+
+    /// Insert a global resource, replacing any previous value of the same type
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.resources.insert(resource)
+    }
+
+    /// Get a global resource
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get::<R>()
+    }
+
+    /// Get a global resource mutably
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut::<R>()
+    }
+
+    /// Get a global resource mutably, inserting its default value if missing
+    pub fn init_resource<R: Resource + Default>(&mut self) -> &mut R {
+        self.resources.get_or_insert_with(R::default)
+    }
+
+    /// Remove a global resource, returning it
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources.remove::<R>()
+    }
+
+    /// Check whether a global resource exists
+    pub fn has_resource<R: Resource>(&self) -> bool {
+        self.resources.contains::<R>()
+    }
+
+    /// Set the insert/remove callbacks for a component type, replacing any
+    /// previous ones. Used to keep world-side indices in sync.
+    pub fn set_component_hooks<T: Component>(&mut self, hooks: ComponentHooks) {
+        self.component_hooks.insert(TypeId::of::<T>(), hooks);
+    }
+
+    /// Register an event type so it is buffered and cleared every frame
+    pub fn add_event<E: Event>(&mut self) {
+        self.init_resource::<Events<E>>();
+        self.event_updaters.entry(TypeId::of::<E>()).or_insert(|resources| {
+            if let Some(events) = resources.get_mut::<Events<E>>() {
+                events.update();
+            }
+        });
+    }
+
+    /// Send an event, registering its type on first use
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        self.add_event::<E>();
+        if let Some(events) = self.resource_mut::<Events<E>>() {
+            events.send(event);
+        }
+    }
+
+    /// Read the events of a type that `reader` has not seen yet
+    pub fn read_events<'a, E: Event>(&'a self, reader: &mut EventReader<E>) -> impl Iterator<Item = &'a E> + use<'a, E> {
+        let events = self.resource::<Events<E>>();
+        let unread = events.map(|events| reader.read(events));
+        unread.into_iter().flatten()
+    }
+
+    /// Frame boundary: drop events (and component removal records) older than
+    /// one frame for every event type.
+    /// Called by the `Scheduler` at the start of each update.
+    pub fn update_events(&mut self) {
+        for update in self.event_updaters.values() {
+            update(&mut self.resources);
+        }
+        self.components.update_removed();
+    }
+
+    /// Run a read-only query, e.g. `world.query::<(Entity, &Position, Option<&Velocity>)>()`
+    pub fn query<Q: ReadOnlyQueryData>(&self) -> QueryIter<'_, Q> {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// Run a read-only query with filters, e.g. `world.query_filtered::<&Position, With<Velocity>>()`
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&self) -> QueryIter<'_, Q, F> {
+        // SAFETY: read-only query data never borrows a storage mutably
+        unsafe { QueryIter::new(QuerySource::new(&self.components), || self.entities.iter().collect()) }
+    }
+
+    /// Run a query that may mutate several components in one pass,
+    /// e.g. `world.query_mut::<(&mut Position, &Velocity)>()`.
+    ///
+    /// Panics if the query borrows the same component mutably more than once.
+    pub fn query_mut<Q: QueryData>(&mut self) -> QueryIter<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Run a mutable query with filters,
+    /// e.g. `world.query_filtered_mut::<&mut Velocity, Without<Player>>()`
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        let entities = &self.entities;
+        // SAFETY: the source borrows the registry exclusively for the iterator's lifetime
+        unsafe { QueryIter::new(QuerySource::new_mut(&mut self.components), || entities.iter().collect()) }
+    }
+
+    /// Add position component to an entity (ignored for dead entities)
     pub fn add_position(&mut self, entity: Entity, position: Position) {
-        self.positions.insert(entity, position);
+        self.insert(entity, position);
     }
 
-    /// Add velocity component to an entity
+    /// Add velocity component to an entity (ignored for dead entities)
     pub fn add_velocity(&mut self, entity: Entity, velocity: Velocity) {
-        self.velocities.insert(entity, velocity);
+        self.insert(entity, velocity);
     }
 
-    /// Add sprite component to an entity
+    /// Add sprite component to an entity (ignored for dead entities)
     pub fn add_sprite(&mut self, entity: Entity, sprite: Sprite) {
-        self.sprites.insert(entity, sprite);
+        self.insert(entity, sprite);
     }
 
-    /// Add texture sprite component to an entity
+    /// Add texture sprite component to an entity (ignored for dead entities)
     pub fn add_texture_sprite(&mut self, entity: Entity, texture_sprite: TextureSprite) {
-        self.texture_sprites.insert(entity, texture_sprite);
+        self.insert(entity, texture_sprite);
     }
 
-    /// Create a physics body for this entity
+    /// Create a physics body for this entity (ignored for dead entities),
+    /// with the defaults from the `PhysicsConfig` resource. Replaces any
+    /// body the entity already has, along with its joints.
     pub fn add_physics_body(&mut self, entity: Entity, position: Position, size: f32, body_type: RigidBodyType) {
+        self.add_physics_body_with_options(entity, position, size, body_type, PhysicsBodyOptions::default());
+    }
+
+    /// Create a physics body for this entity, overriding some `PhysicsConfig` defaults
+    pub fn add_physics_body_with_options(
+        &mut self,
+        entity: Entity,
+        position: Position,
+        size: f32,
+        body_type: RigidBodyType,
+        options: PhysicsBodyOptions,
+    ) {
+        let shape = ColliderShape::square(size);
+        let collider = SharedShape::cuboid(size / 2.0, size / 2.0);
+        self.insert_physics_body(entity, position, shape, collider, body_type, options);
+    }
+
+    /// Create a physics body of any shape for this entity (ignored for dead
+    /// entities), overriding some `PhysicsConfig` defaults
+    pub fn add_physics_body_with_shape(
+        &mut self,
+        entity: Entity,
+        position: Position,
+        shape: ColliderShape,
+        body_type: RigidBodyType,
+        options: PhysicsBodyOptions,
+    ) -> Result<(), ShapeError> {
+        let collider = shape.to_shared_shape()?;
+        self.insert_physics_body(entity, position, shape, collider, body_type, options);
+        Ok(())
+    }
+
+    fn insert_physics_body(
+        &mut self,
+        entity: Entity,
+        position: Position,
+        shape: ColliderShape,
+        collider_shape: SharedShape,
+        body_type: RigidBodyType,
+        options: PhysicsBodyOptions,
+    ) {
+        if !self.is_alive(entity) {
+            return;
+        }
+        // Replacing a body (new shape or options) must not leave the old one simulating
+        self.remove_rigid_body(entity);
+        // The body starts at `position`; an older `Position` would otherwise
+        // count as a gameplay change and teleport it on the next step
+        if self.get::<Position>(entity).is_some_and(|current| current.as_tuple() != position.as_tuple()) {
+            self.insert(entity, position);
+        }
+        let config = self.resource::<PhysicsConfig>().copied().unwrap_or_default();
+
         // Get initial velocity from ECS if it exists
-        let initial_velocity = self.velocities.get(&entity)
+        let initial_velocity = self.get::<Velocity>(entity)
             .map(|v| Vector2::new(v.x, v.y))
             .unwrap_or_else(Vector2::zeros);
 
-        // Create Rapier rigid body with better collision settings and CCD
-        let rigid_body = RigidBodyBuilder::new(body_type)
+        let mut rigid_body = RigidBodyBuilder::new(body_type)
             .translation(Vector2::new(position.x, position.y))
             .linvel(initial_velocity)
-            .linear_damping(0.1)  // Slight damping to prevent infinite bouncing
-            .angular_damping(0.1) // Prevent excessive spinning
-            .can_sleep(false)     // Keep bodies active for better collision response
-            .ccd_enabled(true)    // Enable Continuous Collision Detection
-            .build();
+            .linear_damping(options.linear_damping.unwrap_or(config.linear_damping))
+            .angular_damping(options.angular_damping.unwrap_or(config.angular_damping))
+            .can_sleep(options.can_sleep.unwrap_or(config.can_sleep))
+            .gravity_scale(options.gravity_scale.unwrap_or(1.0))
+            .ccd_enabled(options.ccd.unwrap_or(config.ccd_enabled));
+        if options.lock_rotation {
+            rigid_body = rigid_body.lock_rotations();
+        }
+        let rigid_body = rigid_body.build();
 
         let body_handle = self.physics_world.insert(rigid_body);
 
-        // Calculate appropriate CCD thickness based on object size and potential velocity
-        // Note: In Rapier2D, CCD thickness is automatically calculated based on collider shape
-        // The thickness calculation here serves as documentation for the expected scale
-        let _ccd_thickness = (size / 4.0).max(2.0); // At least 2.0, scaled with object size
-        
-        // Create collider with better collision response settings
         // Note: CCD is enabled at the rigid body level, not collider level in Rapier2D
-        let collider = ColliderBuilder::cuboid(size / 2.0, size / 2.0)
-            .restitution(0.9)     // High bounciness for visible collisions
-            .friction(0.2)        // Low friction for smooth movement
-            .density(1.0)         // Consistent mass
+        let material = options.material.unwrap_or(config.material);
+        let mut collider = ColliderBuilder::new(collider_shape)
+            .restitution(material.restitution)
+            .friction(material.friction)
+            .density(material.density)
             .restitution_combine_rule(CoefficientCombineRule::Max) // Use max restitution
             .friction_combine_rule(CoefficientCombineRule::Average) // Average friction
-            .build();
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .sensor(options.sensor)
+            .collision_groups(options.collision_groups.unwrap_or_default())
+            .solver_groups(options.solver_groups.unwrap_or_default());
+        if options.sensor {
+            // Also notice kinematic characters entering fixed or kinematic zones
+            let kinematic = ActiveCollisionTypes::KINEMATIC_FIXED | ActiveCollisionTypes::KINEMATIC_KINEMATIC;
+            collider = collider.active_collision_types(ActiveCollisionTypes::default() | kinematic);
+        }
+        if let Some(threshold) = options.contact_force_threshold {
+            collider = collider
+                .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(threshold);
+        }
+        if let Some(mass) = options.mass {
+            collider = collider.mass(mass);
+        }
+        let collider = collider.build();
 
         self.collider_set.insert_with_parent(collider, body_handle, &mut self.physics_world);
 
         // Map entity to body handle
         self.entity_to_body.insert(entity, body_handle);
         self.body_to_entity.insert(body_handle, entity);
+        self.insert(entity, PhysicsBody::new(body_handle));
+        let pose = *self.physics_world[body_handle].position();
+        self.insert(entity, PhysicsInterpolation::new(pose));
+        self.insert(entity, shape);
+        if options.is_default() {
+            self.remove::<PhysicsBodyOptions>(entity);
+        } else {
+            self.insert(entity, options);
+        }
+    }
+
+    /// Remove the physics body of an entity, keeping the entity and its components
+    pub fn remove_physics_body(&mut self, entity: Entity) {
+        self.remove_rigid_body(entity);
+        self.remove::<PhysicsBody>(entity);
+        self.remove::<PhysicsInterpolation>(entity);
+        self.remove::<PhysicsBodyOptions>(entity);
+        self.remove::<ColliderShape>(entity);
+    }
+
+    /// Remove the Rapier body of an entity and its colliders
+    fn remove_rigid_body(&mut self, entity: Entity) {
+        let Some(body_handle) = self.entity_to_body.remove(&entity) else {
+            return;
+        };
+        self.body_to_entity.remove(&body_handle);
+        self.joints.retain(|_, joint| joint.entity_a != entity && joint.entity_b != entity);
+        if let Some(body) = self.physics_world.get(body_handle) {
+            for &collider in body.colliders() {
+                let sensor = self.collider_set.get(collider).is_some_and(Collider::is_sensor);
+                self.removed_colliders.insert(collider, (entity, sensor));
+            }
+        }
+        self.physics_world.remove(
+            body_handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true
+        );
+    }
+
+    /// Join the physics bodies of two entities. Returns `None` if either
+    /// has no physics body. The joint goes away with either body.
+    pub fn add_joint(&mut self, entity_a: Entity, entity_b: Entity, desc: JointDesc) -> Option<ImpulseJointHandle> {
+        let body_a = *self.entity_to_body.get(&entity_a)?;
+        let body_b = *self.entity_to_body.get(&entity_b)?;
+        let handle = self.impulse_joint_set.insert(body_a, body_b, desc.to_generic_joint(), true);
+        self.joints.insert(handle, EntityJoint { entity_a, entity_b, desc });
+        Some(handle)
+    }
+
+    /// Remove a joint made by `add_joint`
+    pub fn remove_joint(&mut self, handle: ImpulseJointHandle) -> Option<EntityJoint> {
+        self.impulse_joint_set.remove(handle, true);
+        self.joints.remove(&handle)
+    }
+
+    /// A joint made by `add_joint`
+    pub fn joint(&self, handle: ImpulseJointHandle) -> Option<&EntityJoint> {
+        self.joints.get(&handle)
+    }
+
+    /// Every joint made by `add_joint`
+    pub fn joints(&self) -> impl Iterator<Item = (ImpulseJointHandle, &EntityJoint)> + '_ {
+        self.joints.iter().map(|(&handle, joint)| (handle, joint))
+    }
+
+    /// The joints attached to `entity`
+    pub fn joints_of(&self, entity: Entity) -> impl Iterator<Item = ImpulseJointHandle> + '_ {
+        self.joints
+            .iter()
+            .filter(move |(_, joint)| joint.entity_a == entity || joint.entity_b == entity)
+            .map(|(&handle, _)| handle)
+    }
+
+    /// Change the motor of a revolute or prismatic joint, e.g. to open a
+    /// door. Returns false for other joints.
+    pub fn set_joint_motor(&mut self, handle: ImpulseJointHandle, motor: JointMotor) -> bool {
+        let Some(joint) = self.joints.get_mut(&handle) else {
+            return false;
+        };
+        let Some(axis) = joint.desc.motor_axis() else {
+            return false;
+        };
+        joint.desc = joint.desc.with_motor(motor);
+        let Some(rapier_joint) = self.impulse_joint_set.get_mut(handle) else {
+            return false;
+        };
+        apply_motor(&mut rapier_joint.data, axis, motor);
+        // Sleeping bodies would ignore the new motor
+        for body in [rapier_joint.body1, rapier_joint.body2] {
+            if let Some(body) = self.physics_world.get_mut(body) {
+                body.wake_up(true);
+            }
+        }
+        true
+    }
+
+    /// The entity whose physics body owns `collider`
+    pub fn collider_entity(&self, collider: ColliderHandle) -> Option<Entity> {
+        self.collider_set.get(collider)
+            .and_then(|collider| collider.parent())
+            .and_then(|body| self.body_to_entity.get(&body).copied())
+            .or_else(|| self.removed_colliders.get(&collider).map(|&(entity, _)| entity))
+    }
+
+    /// Whether `collider` is (or, if just removed, was) a sensor
+    fn is_sensor(&self, collider: ColliderHandle) -> bool {
+        match self.collider_set.get(collider) {
+            Some(collider) => collider.is_sensor(),
+            None => self.removed_colliders.get(&collider).is_some_and(|&(_, sensor)| sensor),
+        }
+    }
+
+    /// Entities overlapping the sensor of `entity` or, if it isn't a sensor,
+    /// the sensors it is inside, as of the last physics step
+    pub fn intersections_with(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let colliders = self.entity_to_body.get(&entity)
+            .and_then(|&body| self.physics_world.get(body))
+            .map_or(&[][..], |body| body.colliders());
+        colliders.iter()
+            .flat_map(|&collider| self.narrow_phase.intersection_pairs_with(collider))
+            .filter(|&(_, _, intersecting)| intersecting)
+            .filter_map(move |(collider1, collider2, _)| {
+                let other = self.collider_entity(collider1).filter(|&other| other != entity);
+                other.or_else(|| self.collider_entity(collider2))
+            })
+    }
+
+    /// Refresh `query_pipeline` after bodies were added or moved since the
+    /// last physics step; `step_physics` does this itself
+    pub fn update_query_pipeline(&mut self) {
+        self.query_pipeline.update(&self.collider_set);
+    }
+
+    /// Entities whose colliders contain `point`
+    pub fn entities_at_point(&self, point: Vector2<f32>, filter: &SpatialFilter) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        self.with_query_filter(filter, |query_filter| {
+            self.query_pipeline.intersections_with_point(
+                &self.physics_world,
+                &self.collider_set,
+                &point.into(),
+                query_filter,
+                |collider| {
+                    entities.extend(self.collider_entity(collider));
+                    true
+                },
+            );
+        });
+        entities
+    }
+
+    /// Entities whose colliders overlap `shape` placed at `position`, turned
+    /// by `rotation` radians, e.g. for area damage or placement checks
+    pub fn entities_in_shape(
+        &self,
+        position: Vector2<f32>,
+        rotation: f32,
+        shape: &ColliderShape,
+        filter: &SpatialFilter,
+    ) -> Result<Vec<Entity>, ShapeError> {
+        let shape = shape.to_shared_shape()?;
+        let mut entities = Vec::new();
+        self.with_query_filter(filter, |query_filter| {
+            self.query_pipeline.intersections_with_shape(
+                &self.physics_world,
+                &self.collider_set,
+                &Isometry::new(position, rotation),
+                &*shape,
+                query_filter,
+                |collider| {
+                    entities.extend(self.collider_entity(collider));
+                    true
+                },
+            );
+        });
+        Ok(entities)
+    }
+
+    /// The first collider along the ray from `origin` toward `direction`,
+    /// within `max_toi` times `direction`, e.g. for line-of-sight checks
+    pub fn cast_ray(
+        &self,
+        origin: Vector2<f32>,
+        direction: Vector2<f32>,
+        max_toi: f32,
+        filter: &SpatialFilter,
+    ) -> Option<RayHit> {
+        let ray = Ray::new(origin.into(), direction);
+        let (collider, hit) = self.with_query_filter(filter, |query_filter| {
+            self.query_pipeline.cast_ray_and_get_normal(
+                &self.physics_world,
+                &self.collider_set,
+                &ray,
+                max_toi,
+                true,
+                query_filter,
+            )
+        })?;
+        Some(RayHit {
+            entity: self.collider_entity(collider)?,
+            point: ray.point_at(hit.time_of_impact).coords,
+            normal: hit.normal,
+            toi: hit.time_of_impact,
+        })
+    }
+
+    /// Every collider along a ray, nearest first; see `cast_ray`
+    pub fn cast_ray_all(
+        &self,
+        origin: Vector2<f32>,
+        direction: Vector2<f32>,
+        max_toi: f32,
+        filter: &SpatialFilter,
+    ) -> Vec<RayHit> {
+        let ray = Ray::new(origin.into(), direction);
+        let mut hits = Vec::new();
+        self.with_query_filter(filter, |query_filter| {
+            self.query_pipeline.intersections_with_ray(
+                &self.physics_world,
+                &self.collider_set,
+                &ray,
+                max_toi,
+                true,
+                query_filter,
+                |collider, hit| {
+                    hits.extend(self.collider_entity(collider).map(|entity| RayHit {
+                        entity,
+                        point: ray.point_at(hit.time_of_impact).coords,
+                        normal: hit.normal,
+                        toi: hit.time_of_impact,
+                    }));
+                    true
+                },
+            );
+        });
+        hits.sort_by(|a, b| a.toi.total_cmp(&b.toi));
+        hits
+    }
+
+    /// The first collider `shape` touches when moved from `position` along
+    /// `direction`, within `max_toi` times `direction`, e.g. for ground
+    /// checks with the width of a character
+    pub fn cast_shape(
+        &self,
+        position: Vector2<f32>,
+        rotation: f32,
+        direction: Vector2<f32>,
+        shape: &ColliderShape,
+        max_toi: f32,
+        filter: &SpatialFilter,
+    ) -> Result<Option<RayHit>, ShapeError> {
+        let shape = shape.to_shared_shape()?;
+        let start = Isometry::new(position, rotation);
+        let options = ShapeCastOptions {
+            max_time_of_impact: max_toi,
+            stop_at_penetration: true,
+            compute_impact_geometry_on_penetration: true,
+            ..Default::default()
+        };
+        let hit = self.with_query_filter(filter, |query_filter| {
+            self.query_pipeline.cast_shape(
+                &self.physics_world,
+                &self.collider_set,
+                &start,
+                &direction,
+                &*shape,
+                options,
+                query_filter,
+            )
+        });
+        Ok(hit.and_then(|(collider, hit)| {
+            Some(RayHit {
+                entity: self.collider_entity(collider)?,
+                point: hit.witness1.coords,
+                normal: hit.normal1.into_inner(),
+                toi: hit.time_of_impact,
+            })
+        }))
+    }
+
+    /// Every collider a cast shape touches, nearest first; see `cast_shape`
+    pub fn cast_shape_all(
+        &self,
+        position: Vector2<f32>,
+        rotation: f32,
+        direction: Vector2<f32>,
+        shape: &ColliderShape,
+        max_toi: f32,
+        filter: &SpatialFilter,
+    ) -> Result<Vec<RayHit>, ShapeError> {
+        // Rapier only reports the first hit, so cast again without it
+        let mut filter = filter.clone();
+        let mut hits = Vec::new();
+        while let Some(hit) = self.cast_shape(position, rotation, direction, shape, max_toi, &filter)? {
+            filter.exclude.push(hit.entity);
+            hits.push(hit);
+        }
+        Ok(hits)
+    }
+
+    /// The collider closest to `point`, and the closest point on it
+    pub fn project_point(&self, point: Vector2<f32>, filter: &SpatialFilter) -> Option<PointProjection> {
+        let (collider, projection) = self.with_query_filter(filter, |query_filter| {
+            let point = point.into();
+            self.query_pipeline.project_point(&self.physics_world, &self.collider_set, &point, true, query_filter)
+        })?;
+        Some(PointProjection {
+            entity: self.collider_entity(collider)?,
+            point: projection.point.coords,
+            is_inside: projection.is_inside,
+        })
+    }
+
+    /// Run `query` with the Rapier version of `filter`
+    fn with_query_filter<R>(&self, filter: &SpatialFilter, query: impl FnOnce(RapierQueryFilter) -> R) -> R {
+        let mut flags = QueryFilterFlags::empty();
+        flags.set(QueryFilterFlags::EXCLUDE_SENSORS, filter.exclude_sensors);
+        flags.set(QueryFilterFlags::EXCLUDE_SOLIDS, filter.exclude_solids);
+        let excluded = |collider: ColliderHandle, _: &Collider| {
+            self.collider_entity(collider).is_none_or(|entity| !filter.exclude.contains(&entity))
+        };
+        let mut query_filter = RapierQueryFilter::from(flags);
+        if let Some(groups) = filter.groups {
+            query_filter = query_filter.groups(groups);
+        }
+        if !filter.exclude.is_empty() {
+            query_filter = query_filter.predicate(&excluded);
+        }
+        query(query_filter)
+    }
+
+    /// Attach `child` to `parent`, keeping the child where it is in the world.
+    /// Returns false (and changes nothing) if either entity is dead or the
+    /// link would create a cycle.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> bool {
+        if child == parent || !self.is_alive(child) || !self.is_alive(parent) || self.is_ancestor_of(child, parent) {
+            return false;
+        }
+
+        // Express the child's current world placement in the new parent's space
+        let local = self.global_transform(child).reparented_to(&self.global_transform(parent));
+        self.detach_from_parent(child);
+        self.insert(child, local);
+        self.insert(child, Parent(parent));
+        match self.get_mut::<Children>(parent) {
+            Some(children) => children.0.push(child),
+            None => self.insert(parent, Children(vec![child])),
+        }
+        true
+    }
+
+    /// Detach an entity from its parent, keeping it where it is in the world
+    pub fn remove_parent(&mut self, child: Entity) {
+        if self.has::<Parent>(child) {
+            let global = self.global_transform(child);
+            self.detach_from_parent(child);
+            self.insert(child, Transform::from(global));
+        }
+    }
+
+    /// Remove the `Parent` link and the matching `Children` entry
+    fn detach_from_parent(&mut self, child: Entity) {
+        if let Some(Parent(parent)) = self.remove::<Parent>(child)
+            && let Some(children) = self.get_mut::<Children>(parent)
+        {
+            children.0.retain(|&c| c != child);
+            if children.is_empty() {
+                self.remove::<Children>(parent);
+            }
+        }
+    }
+
+    /// Parent of an entity
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.get::<Parent>(entity).map(Parent::get)
+    }
+
+    /// Children of an entity (empty if it has none)
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.get::<Children>(entity).map_or(&[], Children::as_slice)
+    }
+
+    /// Every entity below `entity` in the hierarchy, depth first
+    pub fn descendants(&self, entity: Entity) -> Vec<Entity> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<Entity> = self.children(entity).iter().rev().copied().collect();
+        while let Some(next) = stack.pop() {
+            descendants.push(next);
+            stack.extend(self.children(next).iter().rev());
+        }
+        descendants
+    }
+
+    /// Whether `ancestor` is a (grand)parent of `entity`
+    pub fn is_ancestor_of(&self, ancestor: Entity, entity: Entity) -> bool {
+        let mut current = self.parent(entity);
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
+            }
+            current = self.parent(parent);
+        }
+        false
+    }
+
+    /// Local transform of an entity; a root's `Position` overrides its translation
+    fn local_transform(&self, entity: Entity) -> Transform {
+        let mut local = self.get::<Transform>(entity).copied().unwrap_or_default();
+        if !self.has::<Parent>(entity)
+            && let Some(position) = self.get::<Position>(entity)
+        {
+            local.translation = Vector2::new(position.x, position.y);
+        }
+        local
+    }
+
+    /// World transform of an entity, computed from the hierarchy right now
+    /// (`GlobalTransform` is only refreshed by `propagate_transforms`)
+    pub fn global_transform(&self, entity: Entity) -> GlobalTransform {
+        let local = self.local_transform(entity);
+        match self.parent(entity) {
+            Some(parent) => self.global_transform(parent).mul_transform(&local),
+            None => local.into(),
+        }
+    }
+
+    /// Recompute `GlobalTransform` for every entity in a hierarchy (or with a
+    /// `Transform`) and move children's `Position` to their world position.
+    /// Only values that actually change are written.
+    pub fn propagate_transforms(&mut self) {
+        let mut roots: Vec<Entity> = self.query_filtered::<Entity, (With<Transform>, Without<Parent>)>()
+            .chain(self.query_filtered::<Entity, (With<Children>, Without<Parent>)>())
+            .collect();
+        roots.sort();
+        roots.dedup();
+
+        for root in roots {
+            let local = self.local_transform(root);
+            if self.get::<Transform>(root).is_some_and(|transform| *transform != local) {
+                self.insert(root, local);
+            }
+            let global = GlobalTransform::from(local);
+            self.set_global_transform(root, global);
+            self.propagate_to_children(root, global);
+        }
+    }
+
+    fn propagate_to_children(&mut self, parent: Entity, parent_global: GlobalTransform) {
+        for child in self.children(parent).to_vec() {
+            let local = self.get::<Transform>(child).copied().unwrap_or_default();
+            let global = parent_global.mul_transform(&local);
+            self.set_global_transform(child, global);
+
+            let (x, y) = (global.translation.x, global.translation.y);
+            if self.get::<Position>(child).is_some_and(|p| p.x != x || p.y != y)
+                && let Some(position) = self.get_mut::<Position>(child)
+            {
+                *position = Position::new(x, y);
+            }
+
+            self.propagate_to_children(child, global);
+        }
+    }
+
+    fn set_global_transform(&mut self, entity: Entity, global: GlobalTransform) {
+        if self.get::<GlobalTransform>(entity) != Some(&global) {
+            self.insert(entity, global);
+        }
+    }
+
+    /// Name an entity, replacing its previous name
+    pub fn set_name(&mut self, entity: Entity, name: impl Into<String>) {
+        self.insert(entity, Name::new(name));
+    }
+
+    /// Name of an entity, if it has one
+    pub fn name(&self, entity: Entity) -> Option<&str> {
+        self.get::<Name>(entity).map(Name::as_str)
+    }
+
+    /// First entity named `name`, in naming order
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.name_index.get(name)?.first().copied()
+    }
+
+    /// Every entity named `name`, in naming order
+    pub fn find_all_by_name(&self, name: &str) -> &[Entity] {
+        self.name_index.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a tag to an entity, creating its `Tags` if needed
+    pub fn add_tag(&mut self, entity: Entity, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.is_alive(entity) || self.has_tag(entity, &tag) {
+            return;
+        }
+        if let Some(tags) = self.components.get_mut::<Tags>(entity) {
+            tags.insert(tag.clone());
+            self.tag_index.entry(tag).or_default().push(entity);
+        } else {
+            self.insert(entity, Tags::new([tag]));
+        }
+    }
+
+    /// Remove a tag from an entity. Returns whether it was present
+    pub fn remove_tag(&mut self, entity: Entity, tag: &str) -> bool {
+        if !self.has_tag(entity, tag) {
+            return false;
+        }
+        if let Some(tags) = self.components.get_mut::<Tags>(entity) {
+            tags.remove(tag);
+        }
+        unindex(&mut self.tag_index, tag, entity);
+        true
+    }
+
+    /// Whether an entity has a tag
+    pub fn has_tag(&self, entity: Entity, tag: &str) -> bool {
+        self.get::<Tags>(entity).is_some_and(|tags| tags.contains(tag))
+    }
+
+    /// Entities carrying `tag`, in tagging order
+    pub fn iter_tagged(&self, tag: &str) -> impl Iterator<Item = Entity> + '_ {
+        self.tag_index.get(tag).into_iter().flatten().copied()
     }
 
     /// Get position component
     pub fn get_position(&self, entity: Entity) -> Option<&Position> {
-        self.positions.get(&entity)
+        self.get(entity)
     }
 
     /// Get mutable position component
     pub fn get_position_mut(&mut self, entity: Entity) -> Option<&mut Position> {
-        self.positions.get_mut(&entity)
+        self.get_mut(entity)
     }
 
     /// Get velocity component
     pub fn get_velocity(&self, entity: Entity) -> Option<&Velocity> {
-        self.velocities.get(&entity)
+        self.get(entity)
     }
 
     /// Get mutable velocity component
     pub fn get_velocity_mut(&mut self, entity: Entity) -> Option<&mut Velocity> {
-        self.velocities.get_mut(&entity)
+        self.get_mut(entity)
     }
 
     /// Get sprite component
     pub fn get_sprite(&self, entity: Entity) -> Option<&Sprite> {
-        self.sprites.get(&entity)
+        self.get(entity)
     }
 
     /// Get texture sprite component
     pub fn get_texture_sprite(&self, entity: Entity) -> Option<&TextureSprite> {
-        self.texture_sprites.get(&entity)
+        self.get(entity)
     }
 
     /// Set velocity of a physics body
     pub fn set_physics_velocity(&mut self, entity: Entity, velocity: Vector2<f32>) {
-        if let Some(&body_handle) = self.entity_to_body.get(&entity) {
-            if let Some(body) = self.physics_world.get_mut(body_handle) {
-                body.set_linvel(velocity, true);
+        if let Some(&body_handle) = self.entity_to_body.get(&entity)
+            && let Some(body) = self.physics_world.get_mut(body_handle)
+        {
+            body.set_linvel(velocity, true);
+        }
+    }
+
+    /// Push `Position`/`Velocity` changes made by gameplay code since the
+    /// last physics write-back into the Rapier bodies
+    pub fn sync_changes_to_physics(&mut self) {
+        let since = self.physics_sync_tick;
+        let mut teleported = Vec::new();
+        for (&entity, &body_handle) in &self.entity_to_body {
+            let Some(body) = self.physics_world.get_mut(body_handle) else {
+                continue;
+            };
+            if let Some(position) = changed_since::<Position>(&self.components, entity, since) {
+                body.set_translation(Vector2::new(position.x, position.y), true);
+                teleported.push((entity, *body.position()));
+            }
+            if let Some(velocity) = changed_since::<Velocity>(&self.components, entity, since) {
+                body.set_linvel(Vector2::new(velocity.x, velocity.y), true);
+            }
+        }
+
+        // Teleports must not be drawn as a slide from the old pose
+        for (entity, pose) in teleported {
+            if let Some(interpolation) = self.components.get_mut::<PhysicsInterpolation>(entity) {
+                interpolation.snap(pose);
             }
         }
     }
 
-    /// Sync positions from physics world to ECS
+    /// Sync positions from physics world to ECS. Only components whose value
+    /// actually differs are written, so `Changed` filters stay meaningful.
     pub fn sync_positions_from_physics(&mut self) {
         for (&entity, &body_handle) in &self.entity_to_body {
             if let Some(body) = self.physics_world.get(body_handle) {
                 let translation = body.translation();
-                if let Some(position) = self.positions.get_mut(&entity) {
+                if self.components.get::<Position>(entity).is_some_and(|p| p.x != translation.x || p.y != translation.y)
+                    && let Some(position) = self.components.get_mut::<Position>(entity)
+                {
                     position.x = translation.x;
                     position.y = translation.y;
                 }
                 
                 let velocity = body.linvel();
-                if let Some(vel) = self.velocities.get_mut(&entity) {
+                if self.components.get::<Velocity>(entity).is_some_and(|v| v.x != velocity.x || v.y != velocity.y)
+                    && let Some(vel) = self.components.get_mut::<Velocity>(entity)
+                {
                     vel.x = velocity.x;
                     vel.y = velocity.y;
                 }
+
+                if let Some(interpolation) = self.components.get_mut::<PhysicsInterpolation>(entity) {
+                    interpolation.push(*body.position());
+                }
             }
         }
+
+        // Our own writes must not be pushed back into Rapier next step
+        self.physics_sync_tick = self.change_tick();
+        self.increment_change_tick();
     }
 
     /// Step the physics simulation
     pub fn step_physics(&mut self) {
-        // Step the physics world with better collision handling
+        // Teleports and velocity changes made by gameplay code
+        self.sync_changes_to_physics();
+
+        let config = self.resource::<PhysicsConfig>().copied().unwrap_or_default();
+        if let Some(iterations) = std::num::NonZeroUsize::new(config.solver_iterations) {
+            self.integration_parameters.num_solver_iterations = iterations;
+        }
+        self.integration_parameters.max_ccd_substeps = config.max_ccd_substeps;
+
+        // Characters go where they can before the bodies they push are simulated
+        self.move_characters(config.gravity);
+
         self.physics_pipeline.step(
-            &Vector2::new(0.0, 0.0), // No gravity - space-like physics
+            &config.gravity,
             &self.integration_parameters,
             &mut self.island_manager,
             &mut self.broad_phase,
@@ -212,130 +1187,516 @@ impl World {
             &mut self.impulse_joint_set,
             &mut self.multibody_joint_set,
             &mut self.ccd_solver,
-            None,
+            Some(&mut self.query_pipeline),
             &self.physics_hooks,
             &self.event_handler,
         );
 
-        // Check for collisions and log them
-        self.check_collisions();
+        // Turn what Rapier reported into engine events
+        self.send_collision_events();
+
+        // Before the write-back, so `Position` never shows a body outside
+        self.apply_world_bounds();
 
         // Sync physics positions back to ECS
         self.sync_positions_from_physics();
-        
-        // Apply boundary constraints to keep entities on screen
-        self.apply_boundary_constraints();
-    }
-
-    /// Check for active collisions and log them
-    fn check_collisions(&self) {
-        // Check contact pairs for active collisions
-        for contact_pair in self.narrow_phase.contact_pairs() {
-            if contact_pair.has_any_active_contact {
-                // Get the colliders involved
-                let collider1 = contact_pair.collider1;
-                let collider2 = contact_pair.collider2;
-                
-                // Find the entities associated with these colliders
-                if let (Some(body1), Some(body2)) = (
-                    self.collider_set.get(collider1).and_then(|c| c.parent()),
-                    self.collider_set.get(collider2).and_then(|c| c.parent())
-                ) {
-                    if let (Some(&entity1), Some(&entity2)) = (
-                        self.body_to_entity.get(&body1),
-                        self.body_to_entity.get(&body2)
-                    ) {
-                        // Get positions for logging
-                        if let (Some(pos1), Some(pos2)) = (
-                            self.positions.get(&entity1),
-                            self.positions.get(&entity2)
-                        ) {
-                            println!("Collision! Entity {} at ({:.1}, {:.1}) <-> Entity {} at ({:.1}, {:.1})",
-                                entity1, pos1.x, pos1.y, entity2, pos2.x, pos2.y);
-                        }
+    }
+
+    /// Move the kinematic body of every `CharacterController` by its
+    /// desired movement and gravity, sliding along whatever it hits
+    fn move_characters(&mut self, gravity: Vector2<f32>) {
+        let characters: Vec<(Entity, CharacterController)> = self.iter::<CharacterController>()
+            .map(|(entity, controller)| (entity, *controller))
+            .collect();
+        if characters.is_empty() {
+            return;
+        }
+        // Catch colliders added since the last step
+        self.update_query_pipeline();
+
+        let dt = self.integration_parameters.dt;
+        let up = UnitVector::try_new(-gravity, 1e-6).unwrap_or(-Vector2::y_axis());
+        // Lowest or highest point of a box along up
+        let height_of = |aabb: &Aabb, lowest: bool| {
+            let heights = aabb.vertices().map(|corner| corner.coords.dot(&up));
+            let (start, pick): (f32, fn(f32, f32) -> f32) = if lowest { (f32::MAX, f32::min) } else { (f32::MIN, f32::max) };
+            heights.into_iter().fold(start, pick)
+        };
+        // One-way platforms and how high their tops are
+        let platforms: Vec<(ColliderHandle, f32)> = self.iter::<PhysicsBodyOptions>()
+            .filter(|(_, options)| options.one_way)
+            .filter_map(|(entity, _)| self.physics_world.get(*self.entity_to_body.get(&entity)?))
+            .flat_map(|body| body.colliders().iter().copied())
+            .filter_map(|handle| Some((handle, height_of(&self.collider_set.get(handle)?.compute_aabb(), false))))
+            .collect();
+
+        for (entity, controller) in characters {
+            let Some(&body_handle) = self.entity_to_body.get(&entity) else {
+                continue;
+            };
+            let Some(&collider_handle) = self.physics_world[body_handle].colliders().first() else {
+                continue;
+            };
+            let collider = &self.collider_set[collider_handle];
+
+            // Gravity pulls even when grounded, which keeps the ground detected
+            let vertical_speed = controller.vertical_speed - gravity.norm() * controller.gravity_scale * dt;
+            let desired = (controller.movement + *up * vertical_speed) * dt;
+
+            // Platforms the feet aren't above yet, give or take a pixel, are passed through
+            let feet = height_of(&collider.compute_aabb(), true);
+            let below: Vec<ColliderHandle> = platforms.iter()
+                .filter(|&&(_, top)| feet < top - 1.0)
+                .map(|&(handle, _)| handle)
+                .collect();
+            let passable = |handle: ColliderHandle, _: &Collider| !below.contains(&handle);
+            let filter = RapierQueryFilter::new()
+                .exclude_rigid_body(body_handle)
+                .exclude_sensors()
+                .groups(collider.collision_groups())
+                .predicate(&passable);
+
+            let rapier_controller = KinematicCharacterController {
+                up,
+                offset: CharacterLength::Absolute(controller.offset),
+                autostep: controller.autostep.map(|step| CharacterAutostep {
+                    max_height: CharacterLength::Absolute(step.max_height),
+                    min_width: CharacterLength::Absolute(step.min_width),
+                    include_dynamic_bodies: false,
+                }),
+                max_slope_climb_angle: controller.max_slope_climb_angle,
+                min_slope_slide_angle: controller.min_slope_slide_angle,
+                snap_to_ground: controller.snap_to_ground.map(CharacterLength::Absolute),
+                ..KinematicCharacterController::default()
+            };
+            let (mut ceiling, mut wall) = (false, false);
+            let climbable = controller.max_slope_climb_angle;
+            let movement = rapier_controller.move_shape(
+                dt,
+                &self.physics_world,
+                &self.collider_set,
+                &self.query_pipeline,
+                collider.shape(),
+                collider.position(),
+                desired,
+                filter,
+                |collision| {
+                    let angle = up.angle(&collision.hit.normal1);
+                    if angle >= std::f32::consts::PI - climbable {
+                        ceiling = true;
+                    } else if angle > climbable {
+                        wall = true;
                     }
+                },
+            );
+
+            let body = &mut self.physics_world[body_handle];
+            body.set_next_kinematic_translation(body.translation() + movement.translation);
+            let Some(controller) = self.components.get_mut::<CharacterController>(entity) else {
+                continue;
+            };
+            controller.grounded = movement.grounded;
+            controller.touching_ceiling = ceiling;
+            controller.touching_wall = wall;
+            let landed = movement.grounded && vertical_speed < 0.0;
+            let bumped = ceiling && vertical_speed > 0.0;
+            controller.vertical_speed = if landed || bumped { 0.0 } else { vertical_speed };
+        }
+    }
+
+    /// Send `CollisionStarted`, `CollisionStopped` and `ContactForce` for
+    /// what the event handler collected during the last step
+    fn send_collision_events(&mut self) {
+        while let Ok(event) = self.collision_events.try_recv() {
+            let entities = (self.collider_entity(event.collider1()), self.collider_entity(event.collider2()));
+            let (Some(entity1), Some(entity2)) = entities else {
+                continue;
+            };
+            if event.sensor() {
+                // Report the sensor first; a sensor touching a sensor counts as the first one
+                let (sensor, entity) = if self.is_sensor(event.collider1()) {
+                    (entity1, entity2)
+                } else {
+                    (entity2, entity1)
+                };
+                if event.started() {
+                    self.send_event(SensorEntered { sensor, entity });
+                } else {
+                    self.send_event(SensorExited { sensor, entity, removed: event.removed() });
                 }
+                continue;
             }
+            match event {
+                CollisionEvent::Started(collider1, collider2, _) => {
+                    let contacts = self.contact_points(collider1, collider2);
+                    self.send_event(CollisionStarted { entity1, entity2, contacts });
+                }
+                CollisionEvent::Stopped(..) => {
+                    self.send_event(CollisionStopped { entity1, entity2, removed: event.removed() });
+                }
+            }
+        }
+        while let Ok(event) = self.contact_force_events.try_recv() {
+            let entities = (self.collider_entity(event.collider1), self.collider_entity(event.collider2));
+            let (Some(entity1), Some(entity2)) = entities else {
+                continue;
+            };
+            let contacts = self.contact_points(event.collider1, event.collider2);
+            self.send_event(ContactForce {
+                entity1,
+                entity2,
+                total_force: event.total_force,
+                total_force_magnitude: event.total_force_magnitude,
+                max_force_direction: event.max_force_direction,
+                max_force_magnitude: event.max_force_magnitude,
+                contacts,
+            });
         }
+        self.removed_colliders.clear();
     }
 
-    /// Keep entities within screen bounds
-    fn apply_boundary_constraints(&mut self) {
-        const SCREEN_WIDTH: f32 = 800.0;
-        const SCREEN_HEIGHT: f32 = 600.0;
-        const MARGIN: f32 = 16.0; // Half of typical sprite size
+    /// Touching points between two colliders, seen from `collider1`
+    fn contact_points(&self, collider1: ColliderHandle, collider2: ColliderHandle) -> Vec<ContactPoint> {
+        let pair = self.narrow_phase.contact_pair(collider1, collider2);
+        let (Some(pair), Some(collider)) = (pair, self.collider_set.get(collider1)) else {
+            return Vec::new();
+        };
+        // The pair may store the colliders the other way round
+        let swapped = pair.collider1 != collider1;
+        let mut contacts = Vec::new();
+        for manifold in pair.manifolds.iter().filter(|manifold| !manifold.data.solver_contacts.is_empty()) {
+            let subshape = if swapped { manifold.subshape_pos2 } else { manifold.subshape_pos1 };
+            let frame = subshape.map_or(*collider.position(), |subshape| collider.position() * subshape);
+            let normal = if swapped { -manifold.data.normal } else { manifold.data.normal };
+            contacts.extend(manifold.points.iter().map(|point| ContactPoint {
+                point: (frame * if swapped { point.local_p2 } else { point.local_p1 }).coords,
+                normal,
+                distance: point.dist,
+                impulse: point.data.impulse,
+            }));
+        }
+        contacts
+    }
 
-        for (&_entity, &body_handle) in &self.entity_to_body {
-            if let Some(body) = self.physics_world.get_mut(body_handle) {
-                let mut translation = *body.translation();
-                let mut velocity = *body.linvel();
-                let mut changed = false;
+    /// Keep dynamic and kinematic bodies inside the `WorldBounds`
+    /// resource, if there is one
+    fn apply_world_bounds(&mut self) {
+        let bounds = self.resource::<WorldBounds>().copied();
+        self.update_bounds_colliders(bounds.filter(|bounds| bounds.behavior == BoundsBehavior::Colliders));
+        let Some(bounds) = bounds.filter(|bounds| bounds.behavior != BoundsBehavior::Colliders) else {
+            return;
+        };
+        let (min, max) = bounds.inner();
+        let size = max - min;
 
-                // Left boundary
-                if translation.x < MARGIN {
-                    translation.x = MARGIN;
-                    velocity.x = velocity.x.abs(); // Bounce right
-                    changed = true;
-                }
-                // Right boundary  
-                else if translation.x > SCREEN_WIDTH - MARGIN {
-                    translation.x = SCREEN_WIDTH - MARGIN;
-                    velocity.x = -velocity.x.abs(); // Bounce left
-                    changed = true;
-                }
+        let mut wrapped = Vec::new();
+        let mut escaped = Vec::new();
+        for (&entity, &body_handle) in &self.entity_to_body {
+            if self.components.get::<PhysicsBodyOptions>(entity).is_some_and(|options| options.ignore_bounds) {
+                continue;
+            }
+            let Some(body) = self.physics_world.get_mut(body_handle) else {
+                continue;
+            };
+            let translation = *body.translation();
+            let inside = translation.sup(&min).inf(&max);
+            if body.is_fixed() || inside == translation {
+                continue;
+            }
 
-                // Top boundary
-                if translation.y < MARGIN {
-                    translation.y = MARGIN;
-                    velocity.y = velocity.y.abs(); // Bounce down
-                    changed = true;
-                }
-                // Bottom boundary
-                else if translation.y > SCREEN_HEIGHT - MARGIN {
-                    translation.y = SCREEN_HEIGHT - MARGIN;
-                    velocity.y = -velocity.y.abs(); // Bounce up
-                    changed = true;
+            match bounds.behavior {
+                BoundsBehavior::Wrap => {
+                    let mut target = translation;
+                    for axis in 0..2 {
+                        // Axes too small to wrap around are clamped instead
+                        if translation[axis] != inside[axis] {
+                            target[axis] = if size[axis] > 0.0 {
+                                min[axis] + (translation[axis] - min[axis]).rem_euclid(size[axis])
+                            } else {
+                                inside[axis]
+                            };
+                        }
+                    }
+                    body.set_translation(target, true);
+                    wrapped.push((entity, *body.position()));
                 }
-
-                if changed {
-                    body.set_translation(translation, true);
+                BoundsBehavior::Clamp | BoundsBehavior::Bounce => {
+                    let bounce = bounds.behavior == BoundsBehavior::Bounce;
+                    let mut velocity = *body.linvel();
+                    for axis in 0..2 {
+                        // Positive when the body is past the max edge, negative past the min one
+                        let outwards = (translation[axis] - inside[axis]).signum();
+                        if translation[axis] != inside[axis] && velocity[axis] * outwards > 0.0 {
+                            velocity[axis] = if bounce { -velocity[axis] } else { 0.0 };
+                        }
+                    }
+                    body.set_translation(inside, true);
                     body.set_linvel(velocity, true);
                 }
+                BoundsBehavior::Despawn => escaped.push((entity, translation)),
+                BoundsBehavior::Colliders => unreachable!("colliders are real bodies, not checked here"),
+            }
+        }
+
+        // Wrapping is a teleport, not a slide across the level
+        for (entity, pose) in wrapped {
+            if let Some(interpolation) = self.components.get_mut::<PhysicsInterpolation>(entity) {
+                interpolation.snap(pose);
+            }
+        }
+        for (entity, position) in escaped {
+            // Children go with an escaped parent
+            if self.is_alive(entity) {
+                self.send_event(OutOfBounds { entity, position });
+                self.remove_entity(entity);
             }
         }
     }
 
-    /// Get entity count
+    /// Entity holding the edge colliders of `BoundsBehavior::Colliders`, if built
+    pub fn bounds_colliders_entity(&self) -> Option<Entity> {
+        self.bounds_colliders.map(|(entity, _)| entity)
+    }
+
+    /// Build, move or remove the fixed edge colliders of
+    /// `BoundsBehavior::Colliders` to match `wanted`
+    fn update_bounds_colliders(&mut self, wanted: Option<WorldBounds>) {
+        // Thick enough that fast bodies don't tunnel through in one step
+        const THICKNESS: f32 = 100.0;
+
+        if let Some((entity, applied)) = self.bounds_colliders
+            && (Some(applied) != wanted || !self.is_alive(entity))
+        {
+            self.remove_entity(entity);
+            self.bounds_colliders = None;
+        }
+        let Some(bounds) = wanted.filter(|_| self.bounds_colliders.is_none()) else {
+            return;
+        };
+
+        let center = (bounds.min + bounds.max) / 2.0;
+        let half = (bounds.max - bounds.min).abs() / 2.0;
+        let (offset_x, offset_y) = (half.x + THICKNESS / 2.0, half.y + THICKNESS / 2.0);
+        let side = ColliderShape::cuboid(THICKNESS / 2.0, half.y + THICKNESS);
+        let edge = ColliderShape::cuboid(half.x + THICKNESS, THICKNESS / 2.0);
+        let shape = ColliderShape::Compound(vec![
+            ShapePart::new((-offset_x, 0.0), side.clone()),
+            ShapePart::new((offset_x, 0.0), side),
+            ShapePart::new((0.0, -offset_y), edge.clone()),
+            ShapePart::new((0.0, offset_y), edge),
+        ]);
+        let position = Position::new(center.x, center.y);
+        let entity = self.spawn((position,));
+        self.set_name(entity, "world_bounds");
+        let options = PhysicsBodyOptions::new().ignoring_bounds();
+        self.add_physics_body_with_shape(entity, position, shape, RigidBodyType::Fixed, options)
+            .expect("edge cuboids are valid compound parts");
+        self.bounds_colliders = Some((entity, bounds));
+    }
+
+    /// Get the number of live entities
     pub fn entity_count(&self) -> usize {
-        self.next_entity_id as usize
+        self.entities.len()
     }
 
-    /// Remove an entity and all its components
+    /// Remove an entity, its descendants and all their components, freeing
+    /// their slots for reuse, and send `EntityDespawned` for each.
+    /// Stale handles are ignored.
     pub fn remove_entity(&mut self, entity: Entity) {
-        // Remove physics body if exists
-        if let Some(&body_handle) = self.entity_to_body.get(&entity) {
-            self.physics_world.remove(
-                body_handle,
-                &mut self.island_manager,
-                &mut self.collider_set,
-                &mut self.impulse_joint_set,
-                &mut self.multibody_joint_set,
-                true
-            );
-            self.entity_to_body.remove(&entity);
-            self.body_to_entity.remove(&body_handle);
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        self.detach_from_parent(entity);
+        for child in self.children(entity).to_vec() {
+            self.remove_entity(child);
+        }
+        let hooks: Vec<ComponentHooks> = self.component_hooks
+            .iter()
+            .filter(|&(&type_id, _)| self.components.contains_type(type_id, entity))
+            .map(|(_, &hooks)| hooks)
+            .collect();
+        for hooks in hooks {
+            (hooks.on_remove)(self, entity);
+        }
+        self.entities.free(entity);
+
+        self.remove_rigid_body(entity);
+
+        // Remove components from every registered storage
+        self.components.remove_entity(entity);
+
+        self.send_event(EntityDespawned { entity });
+    }
+}
+
+fn index_name(world: &mut World, entity: Entity) {
+    if let Some(name) = world.components.get::<Name>(entity) {
+        world.name_index.entry(name.as_str().to_owned()).or_default().push(entity);
+    }
+}
+
+fn unindex_name(world: &mut World, entity: Entity) {
+    if let Some(name) = world.components.get::<Name>(entity) {
+        unindex(&mut world.name_index, name.as_str(), entity);
+    }
+}
+
+fn index_tags(world: &mut World, entity: Entity) {
+    if let Some(tags) = world.components.get::<Tags>(entity) {
+        for tag in tags.iter() {
+            world.tag_index.entry(tag.to_owned()).or_default().push(entity);
+        }
+    }
+}
+
+fn unindex_tags(world: &mut World, entity: Entity) {
+    if let Some(tags) = world.components.get::<Tags>(entity) {
+        for tag in tags.iter() {
+            unindex(&mut world.tag_index, tag, entity);
         }
+    }
+}
 
-        // Remove components
-        self.positions.remove(&entity);
-        self.velocities.remove(&entity);
-        self.sprites.remove(&entity);
-        self.texture_sprites.remove(&entity);
+/// Drop an entity from one key of a name or tag index
+fn unindex(index: &mut HashMap<String, Vec<Entity>>, key: &str, entity: Entity) {
+    if let Some(entities) = index.get_mut(key) {
+        entities.retain(|&e| e != entity);
+        if entities.is_empty() {
+            index.remove(key);
+        }
     }
 }
 
+/// Component of an entity, if it was added or changed after `since`
+fn changed_since<T: Component>(components: &Components, entity: Entity, since: Tick) -> Option<&T> {
+    let storage = components.storage::<T>()?;
+    storage.ticks(entity)?.is_changed(since).then(|| storage.get(entity))?
+}
+
 impl Default for World {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_physics_config_gravity_and_body_overrides() {
+        let mut world = World::new();
+        world.insert_resource(PhysicsConfig::with_gravity(100.0));
+        let falling = world.spawn(());
+        world.add_physics_body(falling, Position::new(100.0, 100.0), 10.0, RigidBodyType::Dynamic);
+        let floating = world.spawn(());
+        let options = PhysicsBodyOptions::new().with_gravity_scale(0.0).with_mass(5.0);
+        world.add_physics_body_with_options(floating, Position::new(200.0, 100.0), 10.0, RigidBodyType::Dynamic, options);
+        assert!(world.get::<PhysicsBodyOptions>(falling).is_none());
+        assert_eq!(world.get::<PhysicsBodyOptions>(floating), Some(&options));
+
+        for _ in 0..30 {
+            world.step_physics();
+        }
+        let y = |entity| world.physics_world[world.entity_to_body[&entity]].translation().y;
+        assert!(y(falling) > 101.0, "gravity pulls the default body down");
+        assert_eq!(y(floating), 100.0);
+        let mass = world.physics_world[world.entity_to_body[&floating]].mass();
+        assert!((mass - 5.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_body_starts_at_its_given_position() {
+        let mut world = World::new();
+        let entity = world.spawn((Position::new(0.0, 0.0),));
+        world.add_physics_body(entity, Position::new(200.0, 150.0), 10.0, RigidBodyType::Fixed);
+        world.step_physics();
+
+        let translation = *world.physics_world[world.entity_to_body[&entity]].translation();
+        assert_eq!((translation.x, translation.y), (200.0, 150.0));
+        assert_eq!(world.get::<Position>(entity).map(|p| p.as_tuple()), Some((200.0, 150.0)));
+    }
+
+    #[test]
+    fn test_adding_a_body_again_replaces_the_old_one() {
+        let mut world = World::new();
+        let entity = world.spawn(());
+        world.add_physics_body(entity, Position::new(100.0, 100.0), 10.0, RigidBodyType::Dynamic);
+        let old = world.entity_to_body[&entity];
+        let shape = ColliderShape::Ball { radius: 8.0 };
+        world
+            .add_physics_body_with_shape(entity, Position::new(100.0, 100.0), shape.clone(), RigidBodyType::Dynamic, PhysicsBodyOptions::new())
+            .unwrap();
+
+        assert_eq!(world.physics_world.len(), 1);
+        assert_eq!(world.collider_set.len(), 1);
+        assert!(world.physics_world.get(old).is_none());
+        assert_eq!(world.body_to_entity.len(), 1);
+        assert_eq!(world.get::<PhysicsBody>(entity).map(|body| body.handle), Some(world.entity_to_body[&entity]));
+        world.step_physics();
+        assert_eq!(world.get::<ColliderShape>(entity), Some(&shape));
+    }
+
+    #[test]
+    fn test_world_bounds_behaviors_and_opt_out() {
+        // A level two screens wide, with bodies heading right at 600 px/s
+        let level = |behavior| WorldBounds::new(Vector2::zeros(), Vector2::new(1600.0, 600.0), behavior);
+        let mut world = World::new();
+        let spawn = |world: &mut World, x: f32, options: PhysicsBodyOptions| {
+            let entity = world.spawn((Position::new(x, 300.0), Velocity::new(600.0, 0.0)));
+            world.add_physics_body_with_options(entity, Position::new(x, 300.0), 10.0, RigidBodyType::Dynamic, options);
+            entity
+        };
+        let steps = |world: &mut World, count: usize| {
+            for _ in 0..count {
+                world.step_physics();
+            }
+        };
+
+        world.insert_resource(level(BoundsBehavior::Wrap));
+        let wrapping = spawn(&mut world, 1590.0, PhysicsBodyOptions::new());
+        steps(&mut world, 2);
+        let x = world.get::<Position>(wrapping).unwrap().x;
+        assert!(x < 20.0, "reappears on the left, x = {}", x);
+        let interpolation = world.get::<PhysicsInterpolation>(wrapping).unwrap();
+        assert_eq!(interpolation.previous, interpolation.current, "not drawn sliding across the level");
+        world.remove_entity(wrapping);
+
+        world.insert_resource(level(BoundsBehavior::Clamp).with_margin(16.0));
+        let clamped = spawn(&mut world, 1500.0, PhysicsBodyOptions::new());
+        let free = spawn(&mut world, 1500.0, PhysicsBodyOptions::new().ignoring_bounds());
+        steps(&mut world, 30);
+        assert_eq!(world.get::<Position>(clamped).unwrap().x, 1584.0);
+        assert_eq!(world.get::<Velocity>(clamped).unwrap().x, 0.0);
+        assert!(world.get::<Position>(free).unwrap().x > 1700.0, "opted out bodies leave");
+        world.remove_entity(clamped);
+
+        world.insert_resource(level(BoundsBehavior::Bounce));
+        let bouncing = spawn(&mut world, 1590.0, PhysicsBodyOptions::new());
+        steps(&mut world, 5);
+        assert!(world.get::<Velocity>(bouncing).unwrap().x < -500.0);
+        assert!(world.get::<Position>(bouncing).unwrap().x < 1600.0);
+        world.remove_entity(bouncing);
+
+        world.insert_resource(level(BoundsBehavior::Despawn));
+        let mut escapes = EventReader::<OutOfBounds>::new();
+        let escaping = spawn(&mut world, 1590.0, PhysicsBodyOptions::new());
+        steps(&mut world, 2);
+        let escaped: Vec<_> = world.read_events(&mut escapes).map(|event| event.entity).collect();
+        assert_eq!(escaped, vec![escaping]);
+        assert!(!world.is_alive(escaping));
+        assert!(world.is_alive(free), "still ignored");
+
+        // Colliders are a real entity that bodies bump into, gone with the resource
+        world.insert_resource(level(BoundsBehavior::Colliders));
+        let walled = spawn(&mut world, 1500.0, PhysicsBodyOptions::new());
+        steps(&mut world, 30);
+        let walls = world.find_by_name("world_bounds").unwrap();
+        assert!(world.get::<Position>(walled).unwrap().x < 1600.0);
+        assert!(world.get::<Velocity>(walled).unwrap().x < 0.0, "bounced off the wall");
+        world.remove_resource::<WorldBounds>();
+        steps(&mut world, 1);
+        assert!(!world.is_alive(walls) && world.find_by_name("world_bounds").is_none());
+    }
+}